@@ -0,0 +1,225 @@
+use std::sync::{Arc, Mutex, MutexGuard, Condvar};
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+use {SharedSplitState, SharedSendState, Pull, SplitOptions, Overflow, Classifier};
+
+
+/// Shared inner state for two `BlockingSplit`s.
+struct SharedBlockingState<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// State shared with the opposite iterator.
+	state: Mutex<SharedSendState<I, P>>,
+	/// Notified whenever the cache shrinks or a side is dropped.
+	changed: Condvar,
+}
+
+impl<I, P> SharedBlockingState<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// Locks the shared state.
+	///
+	/// # Panics
+	///
+	/// Panics if the predicate or the inner iterator panicked while the
+	/// opposite iterator was using them.
+	fn lock(&self) -> MutexGuard<'_, SharedSendState<I, P>> {
+		self.state.lock()
+			.expect("the opposite BlockingSplit panicked")
+	}
+}
+
+
+/// One of a pair of iterators that can be sent to different threads and
+/// that never cache more than a fixed number of items for each other.
+///
+/// If taking the next item would need more items to be cached for the
+/// opposite iterator, `next` blocks until the opposite iterator has taken
+/// some of them or has been dropped.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use split_iter::Splittable;
+///
+/// let (odd, even) = (1..10).split_blocking(1, |v| v % 2 == 0);
+///
+/// let even = thread::spawn(move || even.collect::<Vec<_>>());
+///
+/// assert_eq!(odd.collect::<Vec<_>>(), [1,3,5,7,9]);
+/// assert_eq!(even.join().unwrap(), [2,4,6,8]);
+/// ```
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct BlockingSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// Shared state with the opposite iterator.
+	shared: Arc<SharedBlockingState<I, P>>,
+	/// Is the iterator the right one or the left one?
+	is_right: bool,
+}
+
+impl<I, P> BlockingSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// Creates a pair of `BlockingSplit`s.
+	pub(crate) fn new(iter: I, max_cached: usize, predicate: P)
+		-> (BlockingSplit<I, P>, BlockingSplit<I, P>)
+	{
+		assert!(max_cached > 0, "max_cached must be at least 1");
+		
+		let options = SplitOptions::new()
+			.max_cached(max_cached)
+			.on_overflow(Overflow::Stall);
+		
+		let shared = Arc::new(
+			SharedBlockingState {
+				state: Mutex::new(SharedSplitState::new(iter, predicate, options)),
+				changed: Condvar::new(),
+			}
+		);
+		
+		let left = BlockingSplit {
+			shared: shared.clone(),
+			is_right: false,
+		};
+		
+		let right = BlockingSplit {
+			shared,
+			is_right: true,
+		};
+		
+		(left, right)
+	}
+}
+
+impl<I, P> Iterator for BlockingSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		let shared = &*self.shared;
+		let mut state = shared.lock();
+		
+		loop {
+			match state.pull(self.is_right, false, Iterator::next) {
+				Pull::Item(_, next) => {
+					// The cache might have shrunk
+					shared.changed.notify_all();
+					return Some(next);
+				}
+				Pull::Full => {
+					state = shared.changed.wait(state)
+						.expect("the opposite BlockingSplit panicked");
+				}
+				Pull::Done | Pull::Failed => return None,
+			}
+		}
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.shared.lock().size_hint(self.is_right)
+	}
+}
+
+impl<I, P> FusedIterator for BlockingSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{}
+
+impl<I, P> Drop for BlockingSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	fn drop(&mut self) {
+		// Don't panic while unwinding from a panic in the opposite iterator
+		if let Ok(mut state) = self.shared.state.lock() {
+			state.drop_side(self.is_right);
+		}
+		
+		// Wake up the opposite iterator if it waits for this one
+		self.shared.changed.notify_all();
+	}
+}
+
+impl<I, P> Debug for BlockingSplit<I, P> where
+	I: Iterator + Debug,
+	P: Classifier<I::Item>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("BlockingSplit")
+			.field("iter", &self.shared.lock().iter)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::thread;
+	use std::time::{Duration, Instant};
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use Splittable;
+	
+	#[test]
+	fn concurrent_matches_single_threaded() {
+		let predicate = |v: &u32| v % 7 < 3;
+		let (left, right) = (0..10_000).split_blocking(3, predicate);
+		
+		let left = thread::spawn(move || left.collect::<Vec<_>>());
+		let right = thread::spawn(move || right.collect::<Vec<_>>());
+		
+		let (expected_left, expected_right) = (0..10_000).split(predicate);
+		assert_eq!(left.join().unwrap(), expected_left.collect::<Vec<_>>());
+		assert_eq!(right.join().unwrap(), expected_right.collect::<Vec<_>>());
+	}
+	
+	#[test]
+	fn slow_side_limits_fast_side() {
+		let pulled = Arc::new(AtomicUsize::new(0));
+		let counter = pulled.clone();
+		let source = (0..100).inspect(move |_| {
+			counter.fetch_add(1, Ordering::SeqCst);
+		});
+		let (left, mut right) = source.split_blocking(4, |v| v % 2 == 1);
+		
+		let left = thread::spawn(move || left.collect::<Vec<_>>());
+		
+		// The left side blocks after caching 4 items for the right side
+		let start = Instant::now();
+		while pulled.load(Ordering::SeqCst) < 8 {
+			assert!(start.elapsed() < Duration::from_secs(10), "left side stalled");
+			thread::sleep(Duration::from_millis(1));
+		}
+		thread::sleep(Duration::from_millis(20));
+		assert_eq!(pulled.load(Ordering::SeqCst), 8);
+		
+		assert_eq!(right.next(), Some(1));
+		assert_eq!(right.by_ref().collect::<Vec<_>>(),
+			(3..100).step_by(2).collect::<Vec<_>>());
+		assert_eq!(left.join().unwrap(), (0..100).step_by(2).collect::<Vec<_>>());
+	}
+	
+	#[test]
+	fn dropping_side_unblocks() {
+		let (left, right) = (0..100).split_blocking(1, |v| v % 2 == 1);
+		
+		let left = thread::spawn(move || left.collect::<Vec<_>>());
+		
+		thread::sleep(Duration::from_millis(20));
+		drop(right);
+		
+		assert_eq!(left.join().unwrap(), (0..100).step_by(2).collect::<Vec<_>>());
+	}
+}
@@ -0,0 +1,297 @@
+use std::rc::Rc;
+use std::collections::{HashMap, VecDeque};
+use std::cell::RefCell;
+use std::hash::Hash;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+
+/// Shared inner state for a `Demux` and its `DemuxSplit`s.
+struct SharedDemuxState<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	/// Inner iterator.
+	iter: I,
+	/// Function that chooses the key an item belongs to.
+	key: F,
+	/// Caches that save items that have been skipped by one `DemuxSplit`.
+	/// There is one cache for every key that has items cached; it is removed
+	/// once it is drained.
+	caches: HashMap<K, VecDeque<I::Item>>,
+	/// Has the inner iterator returned `None`?
+	is_exhausted: bool,
+}
+
+impl<I, K, F> SharedDemuxState<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	/// Creates shared inner state for a `Demux`.
+	fn new(iter: I, key: F) -> SharedDemuxState<I, K, F> {
+		SharedDemuxState {
+			iter,
+			key,
+			caches: HashMap::new(),
+			is_exhausted: false,
+		}
+	}
+	
+	/// Returns next item for the given key.
+	fn next(&mut self, key: &K) -> Option<I::Item> {
+		// Use cache for the key
+		if let Some(cache) = self.caches.get_mut(key) {
+			let next = cache.pop_front();
+			if cache.is_empty() {
+				// Don't keep empty caches for keys that are never seen again
+				self.caches.remove(key);
+			}
+			if next.is_some() {
+				return next;
+			}
+		}
+		
+		// From inner iterator
+		while !self.is_exhausted {
+			match self.iter.next() {
+				Some(next) => {
+					let next_key = (self.key)(&next);
+					if next_key == *key {
+						return Some(next);
+					} else {
+						// Fill cache with elements for other keys
+						self.caches.entry(next_key)
+							.or_default()
+							.push_back(next);
+					}
+				},
+				None => self.is_exhausted = true,
+			}
+		}
+		
+		// No element found
+		None
+	}
+	
+	/// Returns the bounds on the remaining length of the iterator for the
+	/// given key.
+	fn size_hint(&self, key: &K) -> (usize, Option<usize>) {
+		let cached = self.caches.get(key).map_or(0, VecDeque::len);
+		if self.is_exhausted {
+			(cached, Some(cached))
+		} else {
+			let (_, upper) = self.iter.size_hint();
+			(cached, upper.and_then(|upper| upper.checked_add(cached)))
+		}
+	}
+}
+
+
+/// Splits an iterator into one iterator per key. Created by
+/// `Splittable::split_by_key`.
+///
+/// Items are cached for every key until a `DemuxSplit` for that key takes
+/// them, so keys that are never asked for keep their items alive for as long
+/// as the `Demux` or one of its `DemuxSplit`s exists.
+///
+/// # Example
+///
+/// ```
+/// use split_iter::Splittable;
+///
+/// let demux = (1..10).split_by_key(|v| v % 3);
+///
+/// assert_eq!(demux.get(&2).collect::<Vec<_>>(), [2,5,8]);
+/// assert_eq!(demux.get(&0).collect::<Vec<_>>(), [3,6,9]);
+/// assert_eq!(demux.get(&1).collect::<Vec<_>>(), [1,4,7]);
+/// ```
+pub struct Demux<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	/// Shared state with all `DemuxSplit`s.
+	shared: Rc<RefCell<SharedDemuxState<I, K, F>>>,
+}
+
+impl<I, K, F> Demux<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	/// Creates a `Demux` for the given iterator.
+	pub(crate) fn new(iter: I, key: F) -> Demux<I, K, F> {
+		Demux {
+			shared: Rc::new(
+				RefCell::new(
+					SharedDemuxState::new(iter, key)
+				)
+			),
+		}
+	}
+	
+	/// Returns an iterator over all items with the given key.
+	///
+	/// Several iterators for the same key share the same items, so every
+	/// item is returned by only one of them.
+	pub fn get(&self, key: &K) -> DemuxSplit<I, K, F> where
+		K: Clone
+	{
+		DemuxSplit {
+			shared: self.shared.clone(),
+			key: key.clone(),
+		}
+	}
+	
+	/// Returns the keys that currently have items cached.
+	pub fn cached_keys(&self) -> Vec<K> where
+		K: Clone
+	{
+		self.shared.borrow().caches.keys().cloned().collect()
+	}
+}
+
+impl<I, K, F> Debug for Demux<I, K, F> where
+	I: Iterator + Debug,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("Demux")
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+
+/// Iterator over the items of a `Demux` with one key.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct DemuxSplit<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	/// Shared state with the `Demux`.
+	shared: Rc<RefCell<SharedDemuxState<I, K, F>>>,
+	/// Key of the items returned by this iterator.
+	key: K,
+}
+
+impl<I, K, F> DemuxSplit<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	/// Returns the key of the items returned by this iterator.
+	pub fn key(&self) -> &K {
+		&self.key
+	}
+}
+
+impl<I, K, F> Iterator for DemuxSplit<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.shared.borrow_mut().next(&self.key)
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.shared.borrow().size_hint(&self.key)
+	}
+}
+
+impl<I, K, F> FusedIterator for DemuxSplit<I, K, F> where
+	I: Iterator,
+	K: Eq + Hash,
+	F: FnMut(&I::Item) -> K
+{}
+
+impl<I, K, F> Debug for DemuxSplit<I, K, F> where
+	I: Iterator + Debug,
+	K: Eq + Hash + Debug,
+	F: FnMut(&I::Item) -> K
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("DemuxSplit")
+			.field("key", &self.key)
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+	use Splittable;
+	
+	#[test]
+	fn keys_appear_while_consuming() {
+		let demux = vec!["a1", "b1", "a2", "c1", "b2"].into_iter()
+			.split_by_key(|s| s.as_bytes()[0]);
+		let mut a = demux.get(&b'a');
+		
+		assert_eq!(a.next(), Some("a1"));
+		assert_eq!(demux.cached_keys(), Vec::<u8>::new());
+		assert_eq!(a.next(), Some("a2"));
+		assert_eq!(demux.cached_keys(), [b'b']);
+		assert_eq!(a.next(), None);
+		
+		let mut keys = demux.cached_keys();
+		keys.sort();
+		assert_eq!(keys, [b'b', b'c']);
+		
+		assert_eq!(demux.get(&b'c').collect::<Vec<_>>(), ["c1"]);
+		assert_eq!(demux.get(&b'b').collect::<Vec<_>>(), ["b1", "b2"]);
+		assert_eq!(demux.get(&b'd').next(), None);
+		assert!(demux.shared.borrow().caches.is_empty());
+	}
+	
+	#[test]
+	fn interleaved_keys() {
+		let demux = (0..30).split_by_key(|v| v % 3);
+		let mut splits = [demux.get(&0), demux.get(&1), demux.get(&2)];
+		let mut results = [Vec::new(), Vec::new(), Vec::new()];
+		
+		for i in (0..30).rev() {
+			let key = i % 3;
+			if let Some(v) = splits[key].next() {
+				results[key].push(v);
+			}
+		}
+		
+		for (key, result) in results.iter().enumerate() {
+			assert_eq!(*result, (0..30).filter(|v| v % 3 == key).collect::<Vec<_>>());
+		}
+	}
+	
+	#[test]
+	fn exhausted_source_is_not_polled_again() {
+		let polled = Cell::new(0);
+		let source = (0..3).inspect(|_| polled.set(polled.get() + 1))
+			.chain(::std::iter::from_fn(|| {
+				polled.set(polled.get() + 1);
+				None
+			}));
+		let demux = source.split_by_key(|v| v % 2);
+		let mut even = demux.get(&0);
+		let mut odd = demux.get(&1);
+		
+		assert_eq!(odd.size_hint(), (0, None));
+		assert_eq!(even.by_ref().collect::<Vec<_>>(), [0, 2]);
+		assert_eq!(odd.size_hint(), (1, Some(1)));
+		assert_eq!(odd.by_ref().collect::<Vec<_>>(), [1]);
+		assert_eq!(even.next(), None);
+		assert_eq!(odd.next(), None);
+		assert_eq!(demux.get(&2).next(), None);
+		assert_eq!(polled.get(), 4);
+	}
+}
@@ -0,0 +1,99 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+use Split;
+
+
+/// One of a pair of iterators like `Split`, that returns every item together
+/// with its position in the inner iterator, counted from zero. Created by
+/// `Splittable::split_enumerated`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitEnumerated<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{
+	/// Split that returns the items.
+	split: Split<I, P>,
+}
+
+impl<I, P> SplitEnumerated<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{
+	/// Wraps a split.
+	pub(crate) fn new(split: Split<I, P>) -> SplitEnumerated<I, P> {
+		SplitEnumerated {
+			split,
+		}
+	}
+	
+	/// Pairs an item with the position the split has saved for it.
+	fn with_index(&self, next: Option<I::Item>) -> Option<(usize, I::Item)> {
+		next.map(|next| {
+			let index = self.split.source_index_of_last()
+				.expect("the inner iterator reported a wrong length");
+			(index, next)
+		})
+	}
+}
+
+impl<I, P> Iterator for SplitEnumerated<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{
+	type Item = (usize, I::Item);
+	
+	fn next(&mut self) -> Option<(usize, I::Item)> {
+		let next = self.split.next();
+		self.with_index(next)
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.split.size_hint()
+	}
+}
+
+impl<I, P> DoubleEndedIterator for SplitEnumerated<I, P> where
+	I: DoubleEndedIterator + ExactSizeIterator,
+	P: FnMut(&I::Item) -> bool
+{
+	fn next_back(&mut self) -> Option<(usize, I::Item)> {
+		let next = self.split.next_back();
+		self.with_index(next)
+	}
+}
+
+impl<I, P> FusedIterator for SplitEnumerated<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{}
+
+impl<I, P> Debug for SplitEnumerated<I, P> where
+	I: Iterator + Debug,
+	P: FnMut(&I::Item) -> bool
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitEnumerated")
+			.field("split", &self.split)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use Splittable;
+	
+	#[test]
+	fn source_indices() {
+		let (mut short, long) = ["a", "bbb", "cc", "d", "eeee"].iter()
+			.split_enumerated(|s| s.len() > 1);
+		
+		assert_eq!(short.next(), Some((0, &"a")));
+		assert_eq!(long.collect::<Vec<_>>(), [(1, &"bbb"), (2, &"cc"), (4, &"eeee")]);
+		assert_eq!(short.next_back(), Some((3, &"d")));
+		assert_eq!(short.next(), None);
+	}
+}
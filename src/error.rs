@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+
+
+/// Reasons why a split can't return its next item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitError {
+	/// The next item can't be taken from the inner iterator, because the
+	/// cache for the opposite side is full and the overflow policy is
+	/// `Overflow::Error`. `Split::next` panics in that case instead.
+	CacheFull,
+	/// The inner iterator returned an error to the opposite side, so no
+	/// more items are taken from it.
+	SourceFailed,
+	/// The split was used from within its own predicate or inner iterator,
+	/// while it was already taking an item.
+	Reentrant,
+	/// The inner iterator or the predicate panicked while the split or the
+	/// opposite one was taking an item.
+	Poisoned,
+}
+
+impl Display for SplitError {
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		match *self {
+			SplitError::CacheFull =>
+				fmt.write_str("the cache for the opposite side is full"),
+			SplitError::SourceFailed =>
+				fmt.write_str("the inner iterator failed on the opposite side"),
+			SplitError::Reentrant =>
+				fmt.write_str("the split was used from within its own predicate \
+					or inner iterator"),
+			SplitError::Poisoned =>
+				fmt.write_str("the inner iterator or the predicate of the split \
+					panicked"),
+		}
+	}
+}
+
+impl Error for SplitError {}
+
+
+/// Error of a fallible predicate, together with the item it failed on.
+/// Returned by the iterators created by `Splittable::try_split`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PredicateError<T, E> {
+	/// Item that couldn't be classified.
+	pub item: T,
+	/// Error returned by the predicate.
+	pub error: E,
+}
+
+impl<T, E> Display for PredicateError<T, E> where
+	E: Display
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		write!(fmt, "the predicate failed: {}", self.error)
+	}
+}
+
+impl<T, E> Error for PredicateError<T, E> where
+	T: Debug,
+	E: Error + 'static
+{
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		Some(&self.error)
+	}
+}
@@ -14,109 +14,898 @@
 //! }
 //! ```
 
+#![allow(clippy::tabs_in_doc_comments)]
+
+#[cfg(feature = "futures")]
+extern crate futures;
+
+
+mod blocking;
+mod demux;
+mod enumerated;
+mod error;
+mod map;
+mod merge;
+mod options;
+mod route;
+mod router;
+mod split_n;
+mod sync;
+mod tee;
+mod try_split;
+mod unzip;
+#[cfg(feature = "futures")]
+mod stream;
+
+pub use blocking::BlockingSplit;
+pub use demux::{Demux, DemuxSplit};
+pub use enumerated::SplitEnumerated;
+pub use error::{SplitError, PredicateError};
+pub use map::{Either, SplitLeft, SplitRight, SplitMapped, Oks, Errs, Somes, Nones};
+pub use merge::{merge_ordered, MergeOrdered, RoutingLog, Logged, LoggedSplit};
+pub use options::{SplitOptions, Overflow};
+pub use route::{Route, Classifier, ByRoute, Indexed, SplitIndexed};
+pub use router::{Router, RoutingTable, RouterSplit, RouteIndex};
+pub use split_n::SplitN;
+pub use sync::SyncSplit;
+pub use tee::Tee;
+pub use try_split::{TrySplit, TryItem, TryPredicate, OkBy, SplitOkBy};
+pub use unzip::{unzip_lazy, Column, Tuple, Field, UnzipLazy};
+#[cfg(feature = "futures")]
+pub use stream::{SplitStream, StreamSplittable};
+
 
 use std::rc::Rc;
 use std::collections::VecDeque;
-use std::cell::RefCell;
+use std::cell::{RefCell, Ref, RefMut};
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Error as FmtError;
+use std::hash::Hash;
+use std::iter::FusedIterator;
+use route::Routed;
+
+
+/// Position of an item in the inner source, if it is known.
+type Position = Option<usize>;
+
+
+/// Outcome of asking the shared state for the next item of one side.
+enum Pull<T> {
+	/// The next item for the side, with its position in the inner source.
+	Item(Position, T),
+	/// The cache for the opposite side is full and the overflow policy
+	/// doesn't allow making room, so no more items can be taken from the
+	/// inner iterator until the opposite side catches up.
+	Full,
+	/// The inner iterator is exhausted.
+	Done,
+	/// The inner iterator returned an error to the opposite side.
+	Failed,
+}
+
+
+/// Receives the items for a side that has been dropped.
+type Orphans<T> = Box<dyn FnMut(T)>;
+
+/// Receives the items for a side that has been dropped, on any thread.
+type SendOrphans<T> = Box<dyn FnMut(T) + Send>;
+
+
+/// Items taken from one end of the inner source that have been skipped by
+/// one side. They will be returned next for the other side.
+///
+/// The items are kept in source order, so the items nearest to the end they
+/// were taken from are the oldest ones.
+struct Cache<T> {
+	/// Cached items with their positions in the inner source.
+	items: VecDeque<(Position, T)>,
+	/// Is the cache currently saving items for the left or for the right side?
+	is_right: bool,
+}
+
+impl<T> Cache<T> {
+	/// Creates an empty cache.
+	fn new() -> Cache<T> {
+		Cache {
+			items: VecDeque::new(),
+			is_right: false,
+		}
+	}
+	
+	/// Returns the number of items cached for the given side.
+	fn len(&self, is_right: bool) -> usize {
+		if is_right == self.is_right {
+			self.items.len()
+		} else {
+			0
+		}
+	}
+	
+	/// Takes the item nearest to the front or to the back of the source, if
+	/// it is cached for the given side.
+	fn pop(&mut self, is_right: bool, from_back: bool) -> Option<(Position, T)> {
+		if is_right != self.is_right {
+			None
+		} else if from_back {
+			self.items.pop_back()
+		} else {
+			self.items.pop_front()
+		}
+	}
+}
+
+
+/// Routing and caching logic for the two sides of a split, independent of
+/// where the items come from.
+struct Sides<T, P, O = Orphans<T>> where
+	P: Classifier<T>,
+	O: FnMut(T)
+{
+	/// Chooses which side an item goes to.
+	classifier: P,
+	/// Cache for items taken from the front of the source.
+	front: Cache<T>,
+	/// Cache for items taken from the back of the source.
+	back: Cache<T>,
+	/// Does the left side still exist?
+	is_left_alive: bool,
+	/// Does the right side still exist?
+	is_right_alive: bool,
+	/// Limit for the size of the caches.
+	options: SplitOptions,
+	/// Receives the items for the left side after it has been dropped,
+	/// instead of discarding them.
+	left_orphans: Option<O>,
+	/// Receives the items for the right side after it has been dropped,
+	/// instead of discarding them.
+	right_orphans: Option<O>,
+	/// Side that received a `Routed::Failure`, if any.
+	failed_side: Option<bool>,
+}
+
+impl<T, P, O> Sides<T, P, O> where
+	P: Classifier<T>,
+	O: FnMut(T)
+{
+	/// Creates the routing state for two sides.
+	fn new(classifier: P, options: SplitOptions) -> Sides<T, P, O> {
+		Sides {
+			classifier,
+			front: Cache::new(),
+			back: Cache::new(),
+			is_left_alive: true,
+			is_right_alive: true,
+			options,
+			left_orphans: None,
+			right_orphans: None,
+			failed_side: None,
+		}
+	}
+	
+	/// Returns the cache for items taken from the front or from the back.
+	fn cache(&mut self, is_back: bool) -> &mut Cache<T> {
+		if is_back {
+			&mut self.back
+		} else {
+			&mut self.front
+		}
+	}
+	
+	/// Returns a cached item for the given side, taken from the front or from
+	/// the back of the source.
+	fn take(&mut self, is_right: bool, is_back: bool) -> Option<(Position, T)> {
+		self.cache(is_back).pop(is_right, is_back)
+	}
+	
+	/// Returns a cached item for the given side from the cache of the
+	/// opposite end. Only valid once the inner source is exhausted, when the
+	/// two caches have met.
+	fn take_rest(&mut self, is_right: bool, is_back: bool)
+		-> Option<(Position, T)>
+	{
+		self.cache(!is_back).pop(is_right, is_back)
+	}
+	
+	/// Returns the number of items cached for the given side.
+	fn cached_len(&self, is_right: bool) -> usize {
+		self.front.len(is_right) + self.back.len(is_right)
+	}
+	
+	/// Returns the item if it goes to the given side. Otherwise caches it for
+	/// the opposite side. If the opposite side doesn't exist anymore, the
+	/// item is passed to the orphan sink or discarded.
+	///
+	/// With `Overflow::Error` and `Overflow::Stall`, the caller has to check
+	/// `is_stalled` first.
+	fn route(&mut self, is_right: bool, is_back: bool, position: Position, next: T)
+		-> Option<T>
+	{
+		match self.classifier.classify(position, next) {
+			Routed::Left(next) if !is_right => Some(next),
+			Routed::Right(next) if is_right => Some(next),
+			Routed::Left(next) | Routed::Right(next) => {
+				self.stash(is_back, !is_right, (position, next));
+				None
+			},
+			Routed::Both(own, other) => {
+				self.stash(is_back, !is_right, (position, other));
+				Some(own)
+			},
+			Routed::Neither => None,
+			Routed::Caller(next) => Some(next),
+			Routed::Failure(next) => {
+				self.failed_side = Some(is_right);
+				Some(next)
+			},
+		}
+	}
+	
+	/// Caches an item for the given side, or passes it to the orphan sink if
+	/// that side doesn't exist anymore.
+	fn stash(&mut self, is_back: bool, is_next_right: bool, next: (Position, T)) {
+		if self.is_alive(is_next_right) {
+			// Fill cache with elements for opposite side
+			self.cache(is_back).is_right = is_next_right;
+			self.push(is_back, next);
+		} else if let Some(ref mut orphans) = *self.orphans(is_next_right) {
+			orphans(next.1);
+		}
+	}
+	
+	/// Returns the orphan sink of the given side.
+	fn orphans(&mut self, is_right: bool) -> &mut Option<O> {
+		if is_right {
+			&mut self.right_orphans
+		} else {
+			&mut self.left_orphans
+		}
+	}
+	
+	/// Adds an item to the cache of the given end, applying the overflow
+	/// policy if the cache is full.
+	fn push(&mut self, is_back: bool, next: (Position, T)) {
+		let options = self.options;
+		let cache = &mut self.cache(is_back).items;
+		
+		if let Some(max_cached) = options.max_cached {
+			if cache.len() >= max_cached {
+				match options.on_overflow {
+					Overflow::Panic => panic!(
+						"more than {} items cached for one side of a split",
+						max_cached
+					),
+					Overflow::DropOldest => {
+						if is_back {
+							cache.pop_back();
+						} else {
+							cache.pop_front();
+						}
+						if max_cached == 0 {
+							return;
+						}
+					}
+					Overflow::DropNewest => return,
+					Overflow::Error | Overflow::Stall => (),
+				}
+			}
+		}
+		
+		if is_back {
+			cache.push_front(next);
+		} else {
+			cache.push_back(next);
+		}
+	}
+	
+	/// Must the given side stop taking items from the given end of the inner
+	/// source, because one more item might overflow the cache of the
+	/// opposite side?
+	fn is_stalled(&mut self, is_right: bool, is_back: bool) -> bool {
+		match self.options.on_overflow {
+			Overflow::Error | Overflow::Stall => (),
+			_ => return false,
+		}
+		
+		let max_cached = self.options.max_cached;
+		let cache = self.cache(is_back);
+		match max_cached {
+			Some(max_cached) => {
+				cache.items.len() >= max_cached &&
+					(cache.items.is_empty() || is_right != cache.is_right)
+			}
+			None => false,
+		}
+	}
+	
+	/// Does the given side still exist?
+	fn is_alive(&self, is_right: bool) -> bool {
+		if is_right {
+			self.is_right_alive
+		} else {
+			self.is_left_alive
+		}
+	}
+	
+	/// Marks the given side as dropped. Its cached items are freed and
+	/// no more items will be cached for it.
+	fn drop_side(&mut self, is_right: bool) {
+		if is_right {
+			self.is_right_alive = false;
+		} else {
+			self.is_left_alive = false;
+		}
+		
+		let orphans = if is_right {
+			&mut self.right_orphans
+		} else {
+			&mut self.left_orphans
+		};
+		for cache in [&mut self.front, &mut self.back] {
+			if is_right == cache.is_right {
+				match *orphans {
+					Some(ref mut orphans) => cache.items.drain(..)
+						.for_each(|(_, item)| orphans(item)),
+					None => cache.items.clear(),
+				}
+			}
+		}
+	}
+	
+	/// Marks the given side as dropped. Its cached items and all items for it
+	/// that are taken from the inner source from now on are passed to
+	/// `orphans`.
+	fn drop_side_into(&mut self, is_right: bool, orphans: O) {
+		*self.orphans(is_right) = Some(orphans);
+		self.drop_side(is_right);
+	}
+}
 
 
 /// Shared inner state for two `Split`s.
-struct SharedSplitState<I, P> where
+struct SharedSplitState<I, P, O = Orphans<<I as Iterator>::Item>> where
 	I: Iterator,
-	P: FnMut(&I::Item) -> bool
+	P: Classifier<I::Item>,
+	O: FnMut(I::Item)
 {
 	/// Inner iterator.
 	iter: I,
-	/// Predicate that chooses whether an item
-	/// goes left (`false`) or right (`true`).
-	predicate: P,
-	/// Cache that saves items that have been skipped by one `Split`.
-	/// They will be returned next for the other `Split`.
-	cache: VecDeque<I::Item>,
-	/// Is the cache currently saving items for the left or for the right split?
-	is_right_cached: bool,
+	/// Has the inner iterator returned `None`? It is never polled again
+	/// afterwards.
+	is_exhausted: bool,
+	/// Number of items taken from the front of the inner iterator.
+	position: usize,
+	/// Did the inner iterator or the classifier panic while an item was
+	/// taken and routed?
+	is_poisoned: bool,
+	/// Routing and caching state of both `Split`s.
+	sides: Sides<I::Item, P, O>,
 }
 
-impl<I, P> SharedSplitState<I, P> where
+/// Shared inner state for two splits that may be used on different threads.
+type SharedSendState<I, P> =
+	SharedSplitState<I, P, SendOrphans<<I as Iterator>::Item>>;
+
+impl<I, P, O> SharedSplitState<I, P, O> where
 	I: Iterator,
-	P: FnMut(&I::Item) -> bool
+	P: Classifier<I::Item>,
+	O: FnMut(I::Item)
 {
 	/// Creates shared inner state for two `Split`s.
-	fn new(iter: I, predicate: P) -> SharedSplitState<I, P> {
+	fn new(iter: I, classifier: P, options: SplitOptions)
+		-> SharedSplitState<I, P, O>
+	{
 		SharedSplitState {
-			iter: iter,
-			predicate: predicate,
-			cache: VecDeque::new(),
-			is_right_cached: false,
+			iter,
+			is_exhausted: false,
+			position: 0,
+			is_poisoned: false,
+			sides: Sides::new(classifier, options),
 		}
 	}
 	
 	/// Returns next item for the given `Split`.
 	fn next(&mut self, is_right: bool) -> Option<I::Item> {
+		self.try_next(is_right).unwrap_or(None).map(|(_, next)| next)
+	}
+	
+	/// Returns next item for the given `Split` with its position, or an
+	/// error if the overflow policy is `Overflow::Error` or `Overflow::Stall`
+	/// and the cache is full.
+	fn try_next(&mut self, is_right: bool)
+		-> Result<Option<(Position, I::Item)>, SplitError>
+	{
+		self.check_poison()?;
+		let pull = self.pull(is_right, false, Iterator::next);
+		self.pull_result(pull)
+	}
+	
+	/// Reports a poisoned state as an error.
+	fn check_poison(&self) -> Result<(), SplitError> {
+		if self.is_poisoned {
+			Err(SplitError::Poisoned)
+		} else {
+			Ok(())
+		}
+	}
+	
+	/// Converts the outcome of a pull into the result of `try_next`.
+	fn pull_result(&self, pull: Pull<I::Item>)
+		-> Result<Option<(Position, I::Item)>, SplitError>
+	{
+		match pull {
+			Pull::Item(position, next) => Ok(Some((position, next))),
+			Pull::Full => Err(SplitError::CacheFull),
+			Pull::Failed => Err(SplitError::SourceFailed),
+			Pull::Done => Ok(None),
+		}
+	}
+	
+	/// Returns next item for the given `Split` from the front or from the
+	/// back, unless the cache for the opposite `Split` would overflow.
+	/// `fetch` takes the next item from the corresponding end of the inner
+	/// iterator.
+	fn pull<F>(&mut self, is_right: bool, is_back: bool, mut fetch: F)
+		-> Pull<I::Item>
+		where F: FnMut(&mut I) -> Option<I::Item>
+	{
 		// Use cache for correct side
-		if is_right == self.is_right_cached {
-			if let Some(next) = self.cache.pop_front() {
-				return Some(next);
-			}
+		if let Some((position, next)) = self.sides.take(is_right, is_back) {
+			return Pull::Item(position, next);
 		}
 		
-		// From inner iterator
-		while let Some(next) = self.iter.next() {
-			if (self.predicate)(&next) == is_right {
-				return Some(next);
-			} else {
-				// Fill cache with elements for opposite iterator
-				self.is_right_cached = !is_right;
-				self.cache.push_back(next);
+		loop {
+			if self.is_exhausted {
+				// The front and the back have met, the remaining items are
+				// in the cache of the opposite end
+				return match self.sides.take_rest(is_right, is_back) {
+					Some((position, next)) => Pull::Item(position, next),
+					None if self.sides.failed_side == Some(!is_right) =>
+						Pull::Failed,
+					None => Pull::Done,
+				};
+			}
+			
+			// Don't cache more items for the opposite side
+			if self.sides.is_stalled(is_right, is_back) {
+				return Pull::Full;
+			}
+			
+			// From inner iterator. The state stays poisoned if the inner
+			// iterator or the classifier panics.
+			let position = self.next_position(is_back);
+			self.is_poisoned = true;
+			let routed = match fetch(&mut self.iter) {
+				Some(next) => {
+					if !is_back {
+						self.position += 1;
+					}
+					let routed = self.sides.route(is_right, is_back, position, next);
+					if self.sides.failed_side.is_some() {
+						// Never take items from a failed source again
+						self.is_exhausted = true;
+					}
+					routed
+				}
+				None => {
+					self.is_exhausted = true;
+					None
+				}
+			};
+			self.is_poisoned = false;
+			
+			if let Some(next) = routed {
+				return Pull::Item(position, next);
 			}
 		}
+	}
+	
+	/// Returns the position of the next item at the front or at the back of
+	/// the inner iterator. At the back, it is only known if the inner
+	/// iterator reports its exact length.
+	fn next_position(&self, is_back: bool) -> Position {
+		if !is_back {
+			return Some(self.position);
+		}
+		
+		match self.iter.size_hint() {
+			(len, Some(upper)) if len == upper && len > 0 =>
+				Some(self.position + len - 1),
+			_ => None,
+		}
+	}
+	
+	/// Returns the bounds on the remaining number of items for the given
+	/// `Split`.
+	fn size_hint(&self, is_right: bool) -> (usize, Option<usize>) {
+		let cached = self.sides.cached_len(is_right);
+		if self.is_exhausted {
+			return (cached, Some(cached));
+		}
 		
-		// No element found
-		None
+		let (_, upper) = self.iter.size_hint();
+		(cached, upper.and_then(|upper| upper.checked_add(cached)))
+	}
+	
+	/// Marks the given `Split` as dropped.
+	fn drop_side(&mut self, is_right: bool) {
+		self.sides.drop_side(is_right);
+	}
+	
+	/// Marks the given `Split` as dropped, passing its items to `orphans`.
+	fn drop_side_into(&mut self, is_right: bool, orphans: O) {
+		self.sides.drop_side_into(is_right, orphans);
 	}
 }
 
+impl<I, P, O> SharedSplitState<I, P, O> where
+	I: DoubleEndedIterator,
+	P: Classifier<I::Item>,
+	O: FnMut(I::Item)
+{
+	/// Returns next item from the back for the given `Split`.
+	fn next_back(&mut self, is_right: bool) -> Option<I::Item> {
+		self.try_next_back(is_right).unwrap_or(None).map(|(_, next)| next)
+	}
+	
+	/// Returns next item from the back for the given `Split` with its
+	/// position, or an error if the overflow policy is `Overflow::Error` or
+	/// `Overflow::Stall` and the cache is full.
+	fn try_next_back(&mut self, is_right: bool)
+		-> Result<Option<(Position, I::Item)>, SplitError>
+	{
+		self.check_poison()?;
+		let pull = self.pull(is_right, true, DoubleEndedIterator::next_back);
+		self.pull_result(pull)
+	}
+}
+
+
+/// Which one of a pair of iterators a `Split` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+	/// The left iterator, which returns the items for which the predicate
+	/// returns `false`.
+	Left,
+	/// The right iterator, which returns the items for which the predicate
+	/// returns `true`.
+	Right,
+}
+
 
 /// One of a pair of iterators. One returns the items for which the predicate
 /// returns `false`, the other one returns the items for which the predicate
 /// returns `true`.
+///
+/// Items are only cached for a `Split` as long as it exists. Once one of the
+/// pair is dropped, the other one works like a filter.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
 pub struct Split<I, P> where
 	I: Iterator,
-	P: FnMut(&I::Item) -> bool
+	P: Classifier<I::Item>
 {
 	/// Shared state with the opposite iterator.
 	shared: Rc<RefCell<SharedSplitState<I, P>>>,
 	/// Is the iterator the right one or the left one?
 	is_right: bool,
+	/// Position in the inner iterator of the last returned item.
+	last_position: Position,
+}
+
+impl<I, P> Split<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// Returns the next item like `next`, but reports a full cache as
+	/// `SplitError::CacheFull` if the split was created with
+	/// `Overflow::Error`, where `next` panics. The split
+	/// returns items again once the opposite one has caught up.
+	///
+	/// Using either split of a pair from within the predicate or the inner
+	/// iterator, while the pair is already taking an item, is reported as
+	/// `SplitError::Reentrant`. `next` panics in that case.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use std::rc::Rc;
+	/// use std::cell::{Cell, RefCell};
+	/// use std::ops::Range;
+	/// use split_iter::{Splittable, Split, SplitError};
+	///
+	/// type Predicate = Box<dyn FnMut(&i32) -> bool>;
+	///
+	/// let sibling = Rc::new(RefCell::new(None::<Split<Range<i32>, Predicate>>));
+	/// let error = Rc::new(Cell::new(None));
+	///
+	/// let (peek, seen) = (sibling.clone(), error.clone());
+	/// let predicate: Predicate = Box::new(move |v| {
+	/// 	// Peek at the right split from within the predicate
+	/// 	if let Some(ref mut right) = *peek.borrow_mut() {
+	/// 		seen.set(right.try_next().err());
+	/// 	}
+	/// 	v % 2 == 1
+	/// });
+	///
+	/// let (mut left, right) = (0..4).split(predicate);
+	/// *sibling.borrow_mut() = Some(right);
+	///
+	/// assert_eq!(left.try_next(), Ok(Some(0)));
+	/// assert_eq!(error.get(), Some(SplitError::Reentrant));
+	/// # sibling.borrow_mut().take();
+	/// ```
+	pub fn try_next(&mut self) -> Result<Option<I::Item>, SplitError> {
+		let next = self.shared_mut()?.try_next(self.is_right);
+		self.remember_position(next)
+	}
+	
+	/// Returns the next item from the back like `next_back`, but reports a
+	/// full cache as `SplitError::CacheFull`, and re-entrant use as
+	/// `SplitError::Reentrant`.
+	pub fn try_next_back(&mut self) -> Result<Option<I::Item>, SplitError> where
+		I: DoubleEndedIterator
+	{
+		let next = self.shared_mut()?.try_next_back(self.is_right);
+		self.remember_position(next)
+	}
+	
+	/// Returns `true` if the inner iterator or the predicate panicked while
+	/// this split or the opposite one was taking an item. Both then report
+	/// `SplitError::Poisoned` from `try_next`, and `next` panics, until the
+	/// poison is cleared.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use std::panic::{catch_unwind, AssertUnwindSafe};
+	/// use split_iter::{Splittable, SplitError};
+	///
+	/// let (mut small, mut large) = (1..10).split(|&v| {
+	/// 	assert!(v != 3, "can't classify 3");
+	/// 	v > 4
+	/// });
+	///
+	/// assert!(catch_unwind(AssertUnwindSafe(|| small.nth(2))).is_err());
+	/// assert!(large.is_poisoned());
+	/// assert_eq!(large.try_next(), Err(SplitError::Poisoned));
+	///
+	/// large.clear_poison();
+	/// assert_eq!(large.next(), Some(5));
+	/// assert_eq!(small.collect::<Vec<_>>(), [4]);
+	/// ```
+	pub fn is_poisoned(&self) -> bool {
+		match self.shared.try_borrow() {
+			Ok(shared) => shared.is_poisoned,
+			// Asked from within the predicate or the inner iterator, which
+			// hasn't panicked so far
+			Err(_) => false,
+		}
+	}
+	
+	/// Clears the poison of this split and the opposite one, so they can be
+	/// used again. The item that was being routed during the panic is lost,
+	/// all other items are still returned.
+	///
+	/// # Panics
+	///
+	/// Panics if it is called from within the predicate or the inner
+	/// iterator.
+	pub fn clear_poison(&self) {
+		expect_usable(self.shared_mut()).is_poisoned = false;
+	}
+	
+	/// Returns whether this is the left or the right split of the pair.
+	pub fn side(&self) -> Side {
+		if self.is_right {
+			Side::Right
+		} else {
+			Side::Left
+		}
+	}
+	
+	/// Returns the number of items cached for both splits of the pair.
+	///
+	/// # Panics
+	///
+	/// Panics if it is called from within the predicate or the inner
+	/// iterator. The same applies to the other methods that inspect the
+	/// shared state.
+	pub fn cached_len(&self) -> usize {
+		let shared = expect_usable(self.shared());
+		shared.sides.front.items.len() + shared.sides.back.items.len()
+	}
+	
+	/// Returns the number of items cached for this split, which it will
+	/// return without taking items from the inner iterator.
+	pub fn cached_for_self(&self) -> usize {
+		expect_usable(self.shared()).sides.cached_len(self.is_right)
+	}
+	
+	/// Returns `true` if the opposite split still exists, so items for it
+	/// are cached.
+	pub fn is_sibling_alive(&self) -> bool {
+		expect_usable(self.shared()).sides.is_alive(!self.is_right)
+	}
+	
+	/// Returns `true` if the inner iterator won't be asked for more items,
+	/// because it returned `None` or failed.
+	pub fn is_source_exhausted(&self) -> bool {
+		expect_usable(self.shared()).is_exhausted
+	}
+	
+	/// Borrows the shared state for reading, unless the pair is taking an
+	/// item.
+	fn shared(&self) -> Result<Ref<'_, SharedSplitState<I, P>>, SplitError> {
+		self.shared.try_borrow().map_err(|_| SplitError::Reentrant)
+	}
+	
+	/// Borrows the shared state, unless the pair is already taking an item.
+	fn shared_mut(&self)
+		-> Result<RefMut<'_, SharedSplitState<I, P>>, SplitError>
+	{
+		self.shared.try_borrow_mut().map_err(|_| SplitError::Reentrant)
+	}
+	
+	/// Returns the position in the inner iterator of the item that was
+	/// returned last, counted from zero.
+	///
+	/// Returns `None` if no item has been returned yet, or if the last item
+	/// was taken from the back of an inner iterator that doesn't report its
+	/// exact length.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use split_iter::Splittable;
+	///
+	/// let (mut small, _) = [9, 1, 8, 2].iter().split(|&&v| v > 5);
+	///
+	/// assert_eq!(small.next(), Some(&1));
+	/// assert_eq!(small.source_index_of_last(), Some(1));
+	/// assert_eq!(small.next(), Some(&2));
+	/// assert_eq!(small.source_index_of_last(), Some(3));
+	/// ```
+	pub fn source_index_of_last(&self) -> Option<usize> {
+		self.last_position
+	}
+	
+	/// Saves the position of a returned item.
+	fn remember_position(
+		&mut self,
+		next: Result<Option<(Position, I::Item)>, SplitError>
+	) -> Result<Option<I::Item>, SplitError>
+	{
+		next.map(|next| next.map(|(position, next)| {
+			self.last_position = position;
+			next
+		}))
+	}
+	
+	/// Drops the iterator, but passes every item that it would have returned
+	/// to `sink` instead of discarding it: first the items that are currently
+	/// cached, then all items for it that the opposite iterator comes across.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use std::rc::Rc;
+	/// use std::cell::Cell;
+	/// use split_iter::Splittable;
+	///
+	/// let dropped = Rc::new(Cell::new(0));
+	/// let counter = dropped.clone();
+	///
+	/// let (odd, even) = (1..10).split(|v| v % 2 == 0);
+	/// even.drop_into(move |_| counter.set(counter.get() + 1));
+	///
+	/// assert_eq!(odd.collect::<Vec<_>>(), [1,3,5,7,9]);
+	/// assert_eq!(dropped.get(), 4);
+	/// ```
+	pub fn drop_into<F>(self, sink: F) where
+		F: FnMut(I::Item) + 'static
+	{
+		expect_usable(self.shared_mut())
+			.drop_side_into(self.is_right, Box::new(sink));
+	}
 }
 
 impl<I, P> Iterator for Split<I, P> where
 	I: Iterator,
-	P: FnMut(&I::Item) -> bool
+	P: Classifier<I::Item>
 {
 	type Item = I::Item;
 	
 	fn next(&mut self) -> Option<I::Item> {
-		self.shared.borrow_mut().next(self.is_right)
+		expect_usable(self.try_next().or_else(end_on_failure))
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self.shared.try_borrow() {
+			Ok(shared) => shared.size_hint(self.is_right),
+			// Asked from within the predicate or the inner iterator
+			Err(_) => (0, None),
+		}
+	}
+}
+
+impl<I, P> DoubleEndedIterator for Split<I, P> where
+	I: DoubleEndedIterator,
+	P: Classifier<I::Item>
+{
+	fn next_back(&mut self) -> Option<I::Item> {
+		expect_usable(self.try_next_back().or_else(end_on_failure))
+	}
+}
+
+impl<I, P> FusedIterator for Split<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{}
+
+impl<I, P> Drop for Split<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	fn drop(&mut self) {
+		// The opposite iterator doesn't need to cache items for this one
+		// anymore. If the state is borrowed, this `Split` is dropped from
+		// within the predicate or the inner iterator; its items are then
+		// still cached, but are freed together with the opposite iterator.
+		if let Ok(mut shared) = self.shared.try_borrow_mut() {
+			shared.drop_side(self.is_right);
+		}
 	}
 }
 
 impl<I, P> Debug for Split<I, P> where
 	I: Iterator + Debug,
-	P: FnMut(&I::Item) -> bool
+	P: Classifier<I::Item>
 {
 	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		let shared = match self.shared() {
+			Ok(shared) => shared,
+			Err(_) => return fmt.debug_struct("Split")
+				.field("side", &self.side())
+				.finish_non_exhaustive(),
+		};
+		
 		fmt.debug_struct("Split")
-			.field("iter", &self.shared.borrow().iter)
+			.field("side", &self.side())
+			.field("cached_for_self", &shared.sides.cached_len(self.is_right))
+			.field("cached_for_sibling", &shared.sides.cached_len(!self.is_right))
+			.field("is_sibling_alive", &shared.sides.is_alive(!self.is_right))
+			.field("is_source_exhausted", &shared.is_exhausted)
+			.field("is_poisoned", &shared.is_poisoned)
+			.field("iter", &shared.iter)
 			.finish()
 	}
 }
 
 
+/// Turns the error that `Iterator::next` reports as the end of a `Split`
+/// into `None`. A failed source never returns items again, so this doesn't
+/// break the `FusedIterator` contract.
+fn end_on_failure<T>(error: SplitError) -> Result<Option<T>, SplitError> {
+	match error {
+		SplitError::SourceFailed => Ok(None),
+		_ => Err(error),
+	}
+}
+
+/// Unwraps the result of using a `Split`, with a clear message for
+/// a full cache, re-entrant use or a poisoned state.
+fn expect_usable<T>(result: Result<T, SplitError>) -> T {
+	match result {
+		Ok(value) => value,
+		Err(SplitError::CacheFull) => panic!(
+			"{}; use try_next to wait for the opposite side",
+			SplitError::CacheFull
+		),
+		Err(error) => panic!("{}", error),
+	}
+}
+
+
 /// Provides an iterator adaptor method that splits an iterator into two
 /// iterators according to a predicate.
 pub trait Splittable<I> where
@@ -127,6 +916,169 @@ pub trait Splittable<I> where
 	/// for which the `predicate` returns `true`.
 	fn split<P>(self, predicate: P) -> (Split<I, P>, Split<I, P>)
 		where P: FnMut(&I::Item) -> bool;
+	
+	/// Splits the iterator like `split`, with a limit for the number of
+	/// cached items and a policy for what happens when it is reached.
+	///
+	/// With `Overflow::Error`, `next` panics when the cache is full, so the
+	/// splits have to be advanced with `Split::try_next`.
+	///
+	/// # Panics
+	///
+	/// Panics with `Overflow::Stall`, which needs `split_blocking`, and if
+	/// `max_cached` is zero with `Overflow::Error`.
+	fn split_with<P>(self, options: SplitOptions, predicate: P)
+		-> (Split<I, P>, Split<I, P>)
+		where P: FnMut(&I::Item) -> bool;
+	
+	/// Splits the iterator like `split`, but also passes the position of each
+	/// item in the iterator, counted from zero, to the `predicate`.
+	///
+	/// Taking items from the back requires an `ExactSizeIterator`, whose
+	/// length tells the positions of the items at the back.
+	fn split_indexed<F>(self, predicate: F)
+		-> (SplitIndexed<I, F>, SplitIndexed<I, F>)
+		where F: FnMut(usize, &I::Item) -> bool;
+	
+	/// Splits the iterator like `split`, but both iterators return every item
+	/// together with its position in this iterator, counted from zero.
+	///
+	/// Taking items from the back requires an `ExactSizeIterator`, whose
+	/// length tells the positions of the items at the back.
+	fn split_enumerated<P>(self, predicate: P)
+		-> (SplitEnumerated<I, P>, SplitEnumerated<I, P>)
+		where P: FnMut(&I::Item) -> bool;
+	
+	/// Splits the iterator like `split`, and also returns a log of the side
+	/// every item went to. Pass the log to `merge_ordered` to put the items
+	/// of the two iterators back into their original order.
+	///
+	/// Items can only be taken from the front of the two iterators, because
+	/// the log records the items in their original order.
+	fn split_logged<P>(self, predicate: P)
+		-> (LoggedSplit<I, P>, LoggedSplit<I, P>, RoutingLog)
+		where P: FnMut(&I::Item) -> bool;
+	
+	/// Splits the iterator like `split`, with a predicate that can fail. Both
+	/// iterators return `Result`s: an item that the predicate fails on is
+	/// returned as an `Err` by the iterator that was taking items from this
+	/// one at the time, together with the error.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use split_iter::Splittable;
+	///
+	/// let (mut odd, mut even) = vec!["1", "2", "x"].into_iter()
+	/// 	.try_split(|s| s.parse::<i32>().map(|v| v % 2 == 0));
+	///
+	/// assert_eq!(even.next(), Some(Ok("2")));
+	/// assert_eq!(even.next().unwrap().unwrap_err().item, "x");
+	/// assert_eq!(odd.next(), Some(Ok("1")));
+	/// assert_eq!(odd.next(), None);
+	/// ```
+	fn try_split<F, E>(self, predicate: F)
+		-> (TrySplit<I, F, E>, TrySplit<I, F, E>)
+		where F: FnMut(&I::Item) -> Result<bool, E>;
+	
+	/// Splits an iterator of `Result`s by a predicate on the `Ok` values.
+	/// Both iterators return `Result`s. The first `Err` is returned by the
+	/// iterator that was taking items from this one at the time, and no more
+	/// items are taken from this one afterwards.
+	///
+	/// Both iterators then end after the items before the `Err` that are
+	/// already cached for them. `SplitOkBy::try_next` of the opposite
+	/// iterator reports the end as `SplitError::SourceFailed`.
+	///
+	/// Items can only be taken from the front of the two iterators, so no
+	/// item after the `Err` is ever returned.
+	fn split_ok_by<T, E, F>(self, predicate: F)
+		-> (SplitOkBy<I, F>, SplitOkBy<I, F>)
+		where I: Iterator<Item = Result<T, E>>, F: FnMut(&T) -> bool;
+	
+	/// Splits the iterator according to a function that returns a `Route`
+	/// for each item. Items can go to the left iterator, to the right
+	/// iterator, to both (as clones) or to neither.
+	fn split_route<F>(self, route: F)
+		-> (Split<I, ByRoute<F>>, Split<I, ByRoute<F>>)
+		where I::Item: Clone, F: FnMut(&I::Item) -> Route;
+	
+	/// Splits the iterator and converts the items at the same time. The
+	/// function `map` takes each item and returns it converted into either
+	/// a value for the left iterator or a value for the right iterator.
+	fn split_map<L, R, F>(self, map: F)
+		-> SplitMapped<I, F, L, R>
+		where F: FnMut(I::Item) -> Either<L, R>;
+	
+	/// Splits an iterator of `Result`s into an iterator over the `Ok` values
+	/// and an iterator over the `Err` values.
+	fn split_results<T, E>(self) -> (Oks<I, T, E>, Errs<I, T, E>)
+		where I: Iterator<Item = Result<T, E>>;
+	
+	/// Splits an iterator of `Option`s into an iterator over the `Some`
+	/// values and an iterator that returns `()` for every `None`.
+	fn split_options<T>(self) -> (Somes<I, T>, Nones<I, T>)
+		where I: Iterator<Item = Option<T>>;
+	
+	/// Duplicates the iterator. Both iterators return every item.
+	fn tee(self) -> (Tee<I>, Tee<I>)
+		where I::Item: Clone;
+	
+	/// Duplicates the iterator `count` times. Every iterator returns every
+	/// item.
+	fn broadcast(self, count: usize) -> Vec<Tee<I>>
+		where I::Item: Clone;
+	
+	/// Splits the iterator into any number of iterators, one for each key
+	/// returned by `key`. The returned `Demux` hands out a lazy iterator for
+	/// any key; items are cached for each key until they are taken.
+	fn split_by_key<K, F>(self, key: F) -> Demux<I, K, F>
+		where K: Eq + Hash, F: FnMut(&I::Item) -> K;
+	
+	/// Splits the iterator like `split`, but returns iterators that can be
+	/// sent to different threads.
+	fn split_sync<P>(self, predicate: P) -> (SyncSplit<I, P>, SyncSplit<I, P>)
+		where I: Send, I::Item: Send, P: FnMut(&I::Item) -> bool + Send;
+	
+	/// Splits the iterator into `N` iterators. The function `index` returns
+	/// the index of the iterator each item goes to.
+	///
+	/// # Panics
+	///
+	/// The iterators panic when `index` returns a value of `N` or more.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use split_iter::Splittable;
+	///
+	/// let [low, mid, high] = (0..9).split_n::<3, _>(|v| v / 3);
+	///
+	/// assert_eq!(high.collect::<Vec<_>>(), [6,7,8]);
+	/// assert_eq!(low.collect::<Vec<_>>(), [0,1,2]);
+	/// assert_eq!(mid.collect::<Vec<_>>(), [3,4,5]);
+	/// ```
+	fn split_n<const N: usize, F>(self, index: F) -> [SplitN<I, F>; N]
+		where F: FnMut(&I::Item) -> usize;
+	
+	/// Splits the iterator with a `Router`. Returns one iterator for each rule
+	/// of the router, in the order they were added, followed by one for the
+	/// fallback.
+	fn split_router<'a>(self, router: RoutingTable<'a, I::Item>)
+		-> Vec<RouterSplit<'a, I>>
+		where I::Item: 'a;
+	
+	/// Splits the iterator like `split_sync`, but never caches more than
+	/// `max_cached` items for one side. A side that would have to cache more
+	/// blocks until the opposite side has taken some of them, or has been
+	/// dropped.
+	///
+	/// # Panics
+	///
+	/// Panics if `max_cached` is zero.
+	fn split_blocking<P>(self, max_cached: usize, predicate: P)
+		-> (BlockingSplit<I, P>, BlockingSplit<I, P>)
+		where I: Send, I::Item: Send, P: FnMut(&I::Item) -> bool + Send;
 }
 
 impl<I> Splittable<I> for I where
@@ -135,30 +1087,194 @@ impl<I> Splittable<I> for I where
 	fn split<P>(self, predicate: P) -> (Split<I, P>, Split<I, P>)
 		where P: FnMut(&I::Item) -> bool
 	{
-		let shared = Rc::new(
-			RefCell::new(
-				SharedSplitState::new(self, predicate)
-			)
-		);
-		
-		let left = Split {
-			shared: shared.clone(),
-			is_right: false,
-		};
-		
-		let right = Split {
-			shared: shared,
-			is_right: true,
-		};
-		
+		self.split_with(SplitOptions::new(), predicate)
+	}
+	
+	fn split_with<P>(self, options: SplitOptions, predicate: P)
+		-> (Split<I, P>, Split<I, P>)
+		where P: FnMut(&I::Item) -> bool
+	{
+		match options.on_overflow {
+			Overflow::Error => assert!(
+				options.max_cached != Some(0),
+				"max_cached must be at least 1 with Overflow::Error"
+			),
+			Overflow::Stall => panic!(
+				"Overflow::Stall needs split_blocking, use Overflow::Error with \
+				Split::try_next instead"
+			),
+			_ => (),
+		}
+		split_classified(self, options, predicate)
+	}
+	
+	fn split_indexed<F>(self, predicate: F)
+		-> (SplitIndexed<I, F>, SplitIndexed<I, F>)
+		where F: FnMut(usize, &I::Item) -> bool
+	{
+		let (left, right) =
+			split_classified(self, SplitOptions::new(), Indexed(predicate));
+		(SplitIndexed::new(left), SplitIndexed::new(right))
+	}
+	
+	fn split_enumerated<P>(self, predicate: P)
+		-> (SplitEnumerated<I, P>, SplitEnumerated<I, P>)
+		where P: FnMut(&I::Item) -> bool
+	{
+		let (left, right) = self.split(predicate);
+		(SplitEnumerated::new(left), SplitEnumerated::new(right))
+	}
+	
+	fn split_logged<P>(self, predicate: P)
+		-> (LoggedSplit<I, P>, LoggedSplit<I, P>, RoutingLog)
+		where P: FnMut(&I::Item) -> bool
+	{
+		let (logged, log) = Logged::new(predicate);
+		let (left, right) = split_classified(self, SplitOptions::new(), logged);
+		(LoggedSplit::new(left), LoggedSplit::new(right), log)
+	}
+	
+	fn try_split<F, E>(self, predicate: F)
+		-> (TrySplit<I, F, E>, TrySplit<I, F, E>)
+		where F: FnMut(&I::Item) -> Result<bool, E>
+	{
+		let items = self.map(Ok as fn(I::Item) -> TryItem<I::Item, E>);
+		split_classified(items, SplitOptions::new(), TryPredicate(predicate))
+	}
+	
+	fn split_ok_by<T, E, F>(self, predicate: F)
+		-> (SplitOkBy<I, F>, SplitOkBy<I, F>)
+		where I: Iterator<Item = Result<T, E>>, F: FnMut(&T) -> bool
+	{
+		let (left, right) =
+			split_classified(self, SplitOptions::new(), OkBy(predicate));
+		(SplitOkBy::new(left), SplitOkBy::new(right))
+	}
+	
+	fn split_route<F>(self, route: F)
+		-> (Split<I, ByRoute<F>>, Split<I, ByRoute<F>>)
+		where I::Item: Clone, F: FnMut(&I::Item) -> Route
+	{
+		split_classified(self, SplitOptions::new(), ByRoute(route))
+	}
+	
+	fn split_map<L, R, F>(self, map: F)
+		-> SplitMapped<I, F, L, R>
+		where F: FnMut(I::Item) -> Either<L, R>
+	{
+		map::split_map(self, map)
+	}
+	
+	fn split_results<T, E>(self) -> (Oks<I, T, E>, Errs<I, T, E>)
+		where I: Iterator<Item = Result<T, E>>
+	{
+		map::split_map(self, map::result_side as fn(Result<T, E>) -> Either<T, E>)
+	}
+	
+	fn split_options<T>(self) -> (Somes<I, T>, Nones<I, T>)
+		where I: Iterator<Item = Option<T>>
+	{
+		map::split_map(self, map::option_side as fn(Option<T>) -> Either<T, ()>)
+	}
+	
+	fn tee(self) -> (Tee<I>, Tee<I>)
+		where I::Item: Clone
+	{
+		let mut tees = tee::broadcast(self, 2);
+		let right = tees.pop().unwrap();
+		let left = tees.pop().unwrap();
 		(left, right)
 	}
+	
+	fn broadcast(self, count: usize) -> Vec<Tee<I>>
+		where I::Item: Clone
+	{
+		tee::broadcast(self, count)
+	}
+	
+	fn split_by_key<K, F>(self, key: F) -> Demux<I, K, F>
+		where K: Eq + Hash, F: FnMut(&I::Item) -> K
+	{
+		Demux::new(self, key)
+	}
+	
+	fn split_n<const N: usize, F>(self, index: F) -> [SplitN<I, F>; N]
+		where F: FnMut(&I::Item) -> usize
+	{
+		split_n::split_n(self, index)
+	}
+	
+	fn split_router<'a>(self, router: RoutingTable<'a, I::Item>)
+		-> Vec<RouterSplit<'a, I>>
+		where I::Item: 'a
+	{
+		let count = router.len();
+		split_n::split_count(self, router.into_index(), count)
+	}
+	
+	fn split_sync<P>(self, predicate: P) -> (SyncSplit<I, P>, SyncSplit<I, P>)
+		where I: Send, I::Item: Send, P: FnMut(&I::Item) -> bool + Send
+	{
+		SyncSplit::new(self, predicate)
+	}
+	
+	fn split_blocking<P>(self, max_cached: usize, predicate: P)
+		-> (BlockingSplit<I, P>, BlockingSplit<I, P>)
+		where I: Send, I::Item: Send, P: FnMut(&I::Item) -> bool + Send
+	{
+		BlockingSplit::new(self, max_cached, predicate)
+	}
+}
+
+
+/// Creates the two sides of a split that uses any `Classifier`.
+fn split_classified<I, P>(iter: I, options: SplitOptions, classifier: P)
+	-> (Split<I, P>, Split<I, P>) where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	let shared = Rc::new(
+		RefCell::new(
+			SharedSplitState::new(iter, classifier, options)
+		)
+	);
+	
+	let left = Split {
+		shared: shared.clone(),
+		is_right: false,
+		last_position: None,
+	};
+	
+	let right = Split {
+		shared,
+		is_right: true,
+		last_position: None,
+	};
+	
+	(left, right)
 }
 
 
 #[cfg(test)]
 mod tests {
-	use super::Splittable;
+	use std::rc::Rc;
+	use std::cell::RefCell;
+	use std::collections::VecDeque;
+	use std::ops::Range;
+	use std::panic::{catch_unwind, AssertUnwindSafe};
+	use super::{Splittable, Split, SplitError, Side};
+	
+	/// Small pseudo-random number generator for property tests.
+	struct Lcg(u64);
+	
+	impl Lcg {
+		fn next(&mut self) -> u64 {
+			self.0 = self.0
+				.wrapping_mul(6364136223846793005)
+				.wrapping_add(1442695040888963407);
+			self.0 >> 33
+		}
+	}
 	
     #[test]
     fn it_works() {
@@ -170,4 +1286,258 @@ mod tests {
 		assert_eq!(high.collect::<Vec<_>>(), (10..20).collect::<Vec<_>>());
 		assert_eq!(low.collect::<Vec<_>>(), (1..10).collect::<Vec<_>>());
     }
+	
+	#[test]
+	fn dropped_side_is_not_cached() {
+		let item = Rc::new(());
+		let items = vec![item.clone(); 10];
+		let (mut left, right) = items.into_iter()
+			.enumerate()
+			.split(|&(i, _)| i % 2 == 1);
+		
+		assert_eq!(left.next().map(|(i, _)| i), Some(0));
+		assert_eq!(left.next().map(|(i, _)| i), Some(2));
+		assert_eq!(Rc::strong_count(&item), 1 + 7 + 1);
+		
+		drop(right);
+		assert_eq!(Rc::strong_count(&item), 1 + 7);
+		
+		assert_eq!(left.map(|(i, _)| i).collect::<Vec<_>>(), [4,6,8]);
+		assert_eq!(Rc::strong_count(&item), 1);
+	}
+	
+	#[test]
+	fn dropped_side_into_sink() {
+		let orphans = Rc::new(RefCell::new(Vec::new()));
+		let sink = orphans.clone();
+		let (mut rest, thirds) = (1..10).split(|v| v % 3 == 0);
+		
+		assert_eq!(rest.next(), Some(1));
+		assert_eq!(rest.next(), Some(2));
+		assert_eq!(rest.next(), Some(4));
+		
+		thirds.drop_into(move |v| sink.borrow_mut().push(v));
+		assert_eq!(*orphans.borrow(), [3]);
+		
+		assert_eq!(rest.collect::<Vec<_>>(), [5,7,8]);
+		assert_eq!(*orphans.borrow(), [3,6,9]);
+	}
+	
+	#[test]
+	fn sibling_dropped_after_drop_into() {
+		let orphans = Rc::new(RefCell::new(Vec::new()));
+		let sink = orphans.clone();
+		let (mut low, high) = (0..10).split(|v| *v >= 5);
+		
+		assert_eq!(low.by_ref().collect::<Vec<_>>(), [0,1,2,3,4]);
+		low.drop_into(move |v| sink.borrow_mut().push(v));
+		drop(high);
+		
+		assert_eq!(*orphans.borrow(), []);
+	}
+	
+	#[test]
+	fn size_hint() {
+		let (mut low, mut high) = (0..10).split(|v| v >= &5);
+		assert_eq!(low.size_hint(), (0, Some(10)));
+		assert_eq!(high.size_hint(), (0, Some(10)));
+		
+		assert_eq!(high.next(), Some(5));
+		assert_eq!(low.size_hint(), (5, Some(9)));
+		assert_eq!(high.size_hint(), (0, Some(4)));
+		
+		assert_eq!(low.next(), Some(0));
+		assert_eq!(low.size_hint(), (4, Some(8)));
+		
+		assert_eq!(high.by_ref().collect::<Vec<_>>(), [6,7,8,9]);
+		assert_eq!(low.size_hint(), (4, Some(4)));
+		assert_eq!(high.size_hint(), (0, Some(0)));
+		
+		let (low, _high) = vec![1, 10, 2, 20].into_iter().split(|v| v >= &10);
+		assert_eq!(low.size_hint(), (0, Some(4)));
+		assert_eq!(low.collect::<Vec<_>>(), [1,2]);
+	}
+	
+	#[test]
+	fn double_ended() {
+		let (odd, even) = (1..10).split(|v| v % 2 == 0);
+		assert_eq!(odd.rev().collect::<Vec<_>>(), [9,7,5,3,1]);
+		assert_eq!(even.rev().collect::<Vec<_>>(), [8,6,4,2]);
+		
+		let (mut low, mut high) = (1..10).split(|v| v >= &5);
+		assert_eq!(low.next_back(), Some(4));
+		assert_eq!(high.next(), Some(5));
+		assert_eq!(low.next(), Some(1));
+		assert_eq!(high.next_back(), Some(9));
+		assert_eq!(low.collect::<Vec<_>>(), [2,3]);
+		assert_eq!(high.collect::<Vec<_>>(), [6,7,8]);
+	}
+	
+	#[test]
+	fn double_ended_matches_partition() {
+		let mut rng = Lcg(1);
+		
+		for _ in 0..500 {
+			let len = rng.next() % 30;
+			let modulus = rng.next() % 4 + 1;
+			let items = (0..len).map(|_| rng.next() % 100).collect::<Vec<_>>();
+			let predicate = |v: &u64| v % modulus == modulus - 1;
+			
+			let (left, right): (Vec<_>, Vec<_>) = items.iter()
+				.partition(|v| !predicate(v));
+			let mut expected = [VecDeque::from(left), VecDeque::from(right)];
+			
+			let (left, right) = items.iter().split(|v| predicate(v));
+			let mut splits = [left, right];
+			
+			if rng.next() % 4 == 3 {
+				// Drain one side from the back
+				let side = (rng.next() % 2) as usize;
+				let rest = splits[side].by_ref().rev().collect::<Vec<_>>();
+				let expected_rest = expected[side].drain(..).rev().collect::<Vec<_>>();
+				assert_eq!(rest, expected_rest);
+			}
+			
+			while !expected[0].is_empty() || !expected[1].is_empty() {
+				let side = (rng.next() % 2) as usize;
+				if rng.next() % 2 == 1 {
+					assert_eq!(splits[side].next(), expected[side].pop_front());
+				} else {
+					assert_eq!(splits[side].next_back(), expected[side].pop_back());
+				}
+			}
+			
+			for split in splits.iter_mut() {
+				assert_eq!(split.next(), None);
+				assert_eq!(split.next_back(), None);
+			}
+		}
+	}
+	
+	#[test]
+	fn fused() {
+		/// Yields `0..4`, then `None`, then `4..8`, and so on, and counts
+		/// how often it is polled.
+		struct Flaky(i32, Rc<RefCell<u32>>);
+		
+		impl Iterator for Flaky {
+			type Item = i32;
+			
+			fn next(&mut self) -> Option<i32> {
+				*self.1.borrow_mut() += 1;
+				self.0 += 1;
+				if self.0 % 5 == 0 {
+					None
+				} else {
+					Some(self.0 - self.0 / 5 - 1)
+				}
+			}
+		}
+		
+		let polls = Rc::new(RefCell::new(0));
+		let (mut odd, mut even) = Flaky(0, polls.clone()).split(|v| v % 2 == 0);
+		
+		assert_eq!(odd.by_ref().collect::<Vec<_>>(), [1,3]);
+		assert_eq!(*polls.borrow(), 5);
+		assert_eq!(odd.next(), None);
+		assert_eq!(even.size_hint(), (2, Some(2)));
+		assert_eq!(even.by_ref().collect::<Vec<_>>(), [0,2]);
+		assert_eq!(even.next(), None);
+		assert_eq!(odd.next(), None);
+		assert_eq!(*polls.borrow(), 5);
+	}
+	
+	type Predicate = Box<dyn FnMut(&i32) -> bool>;
+	type Half = Split<Range<i32>, Predicate>;
+	
+	/// Creates a split whose predicate calls `touch` with the right split.
+	fn touching_sibling<F>(mut touch: F) -> (Half, Rc<RefCell<Option<Half>>>)
+		where F: FnMut(&mut Half) + 'static
+	{
+		let sibling = Rc::new(RefCell::new(None::<Half>));
+		let peek = sibling.clone();
+		let (left, right) = (0..10).split(Box::new(move |v: &i32| {
+			if let Some(ref mut right) = *peek.borrow_mut() {
+				touch(right);
+			}
+			v % 2 == 1
+		}) as Predicate);
+		*sibling.borrow_mut() = Some(right);
+		(left, sibling)
+	}
+	
+	#[test]
+	fn reentrant_try_next() {
+		let errors = Rc::new(RefCell::new(Vec::new()));
+		let seen = errors.clone();
+		let (mut left, sibling) = touching_sibling(move |right| {
+			seen.borrow_mut().push(right.try_next());
+			seen.borrow_mut().push(right.try_next_back());
+			assert_eq!(right.size_hint(), (0, None));
+			assert!(!right.is_poisoned());
+		});
+		
+		assert_eq!(left.try_next(), Ok(Some(0)));
+		assert_eq!(*errors.borrow(), [Err(SplitError::Reentrant); 2]);
+		
+		// The pair keeps working outside of the predicate
+		let mut right = sibling.borrow_mut().take().unwrap();
+		assert_eq!(right.next(), Some(1));
+		assert_eq!(left.next(), Some(2));
+	}
+	
+	#[test]
+	#[should_panic(expected = "the split was used from within its own predicate")]
+	fn reentrant_next_panics() {
+		let (mut left, _sibling) = touching_sibling(|right| {
+			right.next();
+		});
+		left.next();
+	}
+	
+	#[test]
+	fn panic_poisons_both_sides() {
+		let (mut left, mut right) = (0..10).split(|&v| {
+			assert!(v != 4, "predicate failed");
+			v % 2 == 1
+		});
+		
+		assert_eq!(left.next(), Some(0));
+		assert!(catch_unwind(AssertUnwindSafe(|| left.nth(1))).is_err());
+		assert!(left.is_poisoned());
+		assert!(right.is_poisoned());
+		assert_eq!(left.try_next(), Err(SplitError::Poisoned));
+		assert_eq!(right.try_next_back(), Err(SplitError::Poisoned));
+		assert!(catch_unwind(AssertUnwindSafe(|| right.next())).is_err());
+		
+		// The item that was routed during the panic is lost
+		right.clear_poison();
+		assert!(!left.is_poisoned());
+		assert_eq!(right.collect::<Vec<_>>(), [1, 3, 5, 7, 9]);
+		assert_eq!(left.collect::<Vec<_>>(), [6, 8]);
+	}
+	
+	#[test]
+	fn introspection() {
+		let (mut small, large) = (0..6).split(|&v| v >= 3);
+		
+		assert_eq!(small.side(), Side::Left);
+		assert_eq!(large.side(), Side::Right);
+		assert_eq!(small.by_ref().last(), Some(2));
+		assert_eq!(small.cached_len(), 3);
+		assert_eq!(small.cached_for_self(), 0);
+		assert_eq!(large.cached_for_self(), 3);
+		assert!(small.is_source_exhausted());
+		assert!(small.is_sibling_alive());
+		assert_eq!(
+			format!("{:?}", large),
+			"Split { side: Right, cached_for_self: 3, cached_for_sibling: 0, \
+				is_sibling_alive: true, is_source_exhausted: true, \
+				is_poisoned: false, iter: 6..6 }"
+		);
+		
+		drop(large);
+		assert!(!small.is_sibling_alive());
+		assert_eq!(small.cached_len(), 0);
+	}
 }
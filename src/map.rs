@@ -0,0 +1,356 @@
+use std::rc::Rc;
+use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+
+/// A value of one of two types. Returned by the function passed to
+/// `Splittable::split_map` to choose the side of an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Either<L, R> {
+	/// A value for the left iterator.
+	Left(L),
+	/// A value for the right iterator.
+	Right(R),
+}
+
+
+/// Pair of iterators created by `Splittable::split_map`.
+pub type SplitMapped<I, F, L, R> = (SplitLeft<I, F, L, R>, SplitRight<I, F, L, R>);
+
+/// Iterator over the `Ok` values of a split iterator of `Result`s. Created by
+/// `Splittable::split_results`.
+pub type Oks<I, T, E> = SplitLeft<I, fn(Result<T, E>) -> Either<T, E>, T, E>;
+
+/// Iterator over the `Err` values of a split iterator of `Result`s. Created by
+/// `Splittable::split_results`.
+pub type Errs<I, T, E> = SplitRight<I, fn(Result<T, E>) -> Either<T, E>, T, E>;
+
+/// Iterator over the `Some` values of a split iterator of `Option`s. Created
+/// by `Splittable::split_options`.
+pub type Somes<I, T> = SplitLeft<I, fn(Option<T>) -> Either<T, ()>, T, ()>;
+
+/// Iterator that returns `()` for every `None` of a split iterator of
+/// `Option`s. Created by `Splittable::split_options`.
+pub type Nones<I, T> = SplitRight<I, fn(Option<T>) -> Either<T, ()>, T, ()>;
+
+/// Sends `Ok` values left and `Err` values right.
+pub(crate) fn result_side<T, E>(result: Result<T, E>) -> Either<T, E> {
+	match result {
+		Ok(value) => Either::Left(value),
+		Err(error) => Either::Right(error),
+	}
+}
+
+/// Sends `Some` values left and `None`s right.
+pub(crate) fn option_side<T>(option: Option<T>) -> Either<T, ()> {
+	match option {
+		Some(value) => Either::Left(value),
+		None => Either::Right(()),
+	}
+}
+
+
+/// Shared inner state for a `SplitLeft` and a `SplitRight`.
+struct SharedMapState<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	/// Inner iterator.
+	iter: I,
+	/// Function that converts an item and chooses its side.
+	map: F,
+	/// Converted items that have been skipped by the right iterator.
+	left: VecDeque<L>,
+	/// Converted items that have been skipped by the left iterator.
+	right: VecDeque<R>,
+	/// Has the inner iterator returned `None`?
+	is_exhausted: bool,
+	/// Does the left iterator still exist?
+	is_left_alive: bool,
+	/// Does the right iterator still exist?
+	is_right_alive: bool,
+}
+
+impl<I, F, L, R> SharedMapState<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	/// Creates shared inner state for a `SplitLeft` and a `SplitRight`.
+	fn new(iter: I, map: F) -> SharedMapState<I, F, L, R> {
+		SharedMapState {
+			iter,
+			map,
+			left: VecDeque::new(),
+			right: VecDeque::new(),
+			is_exhausted: false,
+			is_left_alive: true,
+			is_right_alive: true,
+		}
+	}
+	
+	/// Returns the next converted item for the left iterator.
+	fn next_left(&mut self) -> Option<L> {
+		// Use cache
+		if let Some(next) = self.left.pop_front() {
+			return Some(next);
+		}
+		
+		// From inner iterator
+		while let Some(next) = self.next_mapped() {
+			match next {
+				Either::Left(next) => return Some(next),
+				Either::Right(next) => if self.is_right_alive {
+					self.right.push_back(next);
+				},
+			}
+		}
+		
+		// No element found
+		None
+	}
+	
+	/// Returns the next converted item for the right iterator.
+	fn next_right(&mut self) -> Option<R> {
+		// Use cache
+		if let Some(next) = self.right.pop_front() {
+			return Some(next);
+		}
+		
+		// From inner iterator
+		while let Some(next) = self.next_mapped() {
+			match next {
+				Either::Left(next) => if self.is_left_alive {
+					self.left.push_back(next);
+				},
+				Either::Right(next) => return Some(next),
+			}
+		}
+		
+		// No element found
+		None
+	}
+	
+	/// Takes the next item from the inner iterator and converts it.
+	fn next_mapped(&mut self) -> Option<Either<L, R>> {
+		if self.is_exhausted {
+			return None;
+		}
+		
+		match self.iter.next() {
+			Some(next) => Some((self.map)(next)),
+			None => {
+				self.is_exhausted = true;
+				None
+			}
+		}
+	}
+	
+	/// Returns the bounds on the remaining number of items for an iterator
+	/// with `cached` items cached.
+	fn size_hint(&self, cached: usize) -> (usize, Option<usize>) {
+		if self.is_exhausted {
+			return (cached, Some(cached));
+		}
+		
+		let (_, upper) = self.iter.size_hint();
+		(cached, upper.and_then(|upper| upper.checked_add(cached)))
+	}
+}
+
+
+/// The left one of a pair of iterators created by `Splittable::split_map`.
+/// Returns the converted items for which the function returns
+/// `Either::Left`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitLeft<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	/// Shared state with the right iterator.
+	shared: Rc<RefCell<SharedMapState<I, F, L, R>>>,
+}
+
+/// The right one of a pair of iterators created by `Splittable::split_map`.
+/// Returns the converted items for which the function returns
+/// `Either::Right`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitRight<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	/// Shared state with the left iterator.
+	shared: Rc<RefCell<SharedMapState<I, F, L, R>>>,
+}
+
+/// Creates a `SplitLeft` and a `SplitRight` for the given iterator.
+pub(crate) fn split_map<I, F, L, R>(iter: I, map: F)
+	-> SplitMapped<I, F, L, R>
+	where I: Iterator, F: FnMut(I::Item) -> Either<L, R>
+{
+	let shared = Rc::new(
+		RefCell::new(
+			SharedMapState::new(iter, map)
+		)
+	);
+	
+	let left = SplitLeft {
+		shared: shared.clone(),
+	};
+	
+	let right = SplitRight {
+		shared,
+	};
+	
+	(left, right)
+}
+
+impl<I, F, L, R> Iterator for SplitLeft<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	type Item = L;
+	
+	fn next(&mut self) -> Option<L> {
+		self.shared.borrow_mut().next_left()
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let shared = self.shared.borrow();
+		shared.size_hint(shared.left.len())
+	}
+}
+
+impl<I, F, L, R> Iterator for SplitRight<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	type Item = R;
+	
+	fn next(&mut self) -> Option<R> {
+		self.shared.borrow_mut().next_right()
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let shared = self.shared.borrow();
+		shared.size_hint(shared.right.len())
+	}
+}
+
+impl<I, F, L, R> FusedIterator for SplitLeft<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{}
+
+impl<I, F, L, R> FusedIterator for SplitRight<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{}
+
+impl<I, F, L, R> Drop for SplitLeft<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	fn drop(&mut self) {
+		if let Ok(mut shared) = self.shared.try_borrow_mut() {
+			shared.is_left_alive = false;
+			shared.left.clear();
+		}
+	}
+}
+
+impl<I, F, L, R> Drop for SplitRight<I, F, L, R> where
+	I: Iterator,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	fn drop(&mut self) {
+		if let Ok(mut shared) = self.shared.try_borrow_mut() {
+			shared.is_right_alive = false;
+			shared.right.clear();
+		}
+	}
+}
+
+impl<I, F, L, R> Debug for SplitLeft<I, F, L, R> where
+	I: Iterator + Debug,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitLeft")
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+impl<I, F, L, R> Debug for SplitRight<I, F, L, R> where
+	I: Iterator + Debug,
+	F: FnMut(I::Item) -> Either<L, R>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitRight")
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::num::ParseIntError;
+	use {Splittable, Either};
+	
+	#[test]
+	fn converts_items() {
+		let (numbers, words) = vec!["1", "one", "2", "two", "three"].into_iter()
+			.split_map(|s| match s.parse::<u32>() {
+				Ok(n) => Either::Left(n),
+				Err(_) => Either::Right(s.len()),
+			});
+		
+		assert_eq!(words.size_hint(), (0, Some(5)));
+		assert_eq!(words.collect::<Vec<_>>(), [3,3,5]);
+		assert_eq!(numbers.size_hint(), (2, Some(2)));
+		assert_eq!(numbers.collect::<Vec<_>>(), [1,2]);
+	}
+	
+	#[test]
+	fn interleaved() {
+		let (mut odd, mut even) = (1..10).split_map(|v| {
+			if v % 2 == 0 {
+				Either::Right(v as f64 / 2.0)
+			} else {
+				Either::Left(v.to_string())
+			}
+		});
+		
+		assert_eq!(even.next(), Some(1.0));
+		assert_eq!(odd.next(), Some("1".to_string()));
+		assert_eq!(odd.next(), Some("3".to_string()));
+		assert_eq!(even.collect::<Vec<_>>(), [2.0, 3.0, 4.0]);
+		assert_eq!(odd.collect::<Vec<_>>(), ["5", "7", "9"]);
+	}
+	
+	#[test]
+	fn results() {
+		let (numbers, errors) = vec!["1", "x", "2", "", "3"].into_iter()
+			.map(|s| s.parse::<u32>())
+			.split_results();
+		
+		let errors: Vec<ParseIntError> = errors.collect();
+		assert_eq!(errors.len(), 2);
+		assert_eq!(numbers.collect::<Vec<_>>(), [1,2,3]);
+	}
+	
+	#[test]
+	fn options() {
+		let (values, mut nones) = vec![Some(1), None, Some(2), None, None].into_iter()
+			.split_options();
+		
+		assert_eq!(nones.next(), Some(()));
+		assert_eq!(values.collect::<Vec<_>>(), [1,2]);
+		assert_eq!(nones.count(), 2);
+	}
+}
@@ -0,0 +1,345 @@
+use std::rc::Rc;
+use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+use {Split, Classifier};
+use route::Routed;
+
+
+/// Bits of a `RoutingLog`.
+struct LogBits {
+	/// One bit for each logged item, set if it went right. Words for items
+	/// that have been merged already are removed.
+	words: VecDeque<u64>,
+	/// Position of the item of the lowest bit of the first word.
+	first: usize,
+	/// Number of items logged so far.
+	len: usize,
+}
+
+impl LogBits {
+	/// Saves the side of the next item.
+	fn push(&mut self, is_right: bool) {
+		let index = self.len - self.first;
+		if self.words.len() <= index / 64 {
+			self.words.push_back(0);
+		}
+		if is_right {
+			self.words[index / 64] |= 1 << (index % 64);
+		}
+		self.len += 1;
+	}
+	
+	/// Returns whether the item at the given position went right, if it has
+	/// been logged.
+	fn is_right(&self, position: usize) -> Option<bool> {
+		if position < self.first || position >= self.len {
+			return None;
+		}
+		
+		let index = position - self.first;
+		Some(self.words[index / 64] & (1 << (index % 64)) != 0)
+	}
+	
+	/// Frees the bits of all items before the given position.
+	fn forget_before(&mut self, position: usize) {
+		while self.first + 64 <= position && !self.words.is_empty() {
+			self.words.pop_front();
+			self.first += 64;
+		}
+	}
+}
+
+
+/// Record of which side every item of a split went to, with one bit per
+/// item. Created by `Splittable::split_logged` and used by `merge_ordered`.
+#[derive(Clone)]
+pub struct RoutingLog {
+	/// Bits shared with the classifier that records them.
+	bits: Rc<RefCell<LogBits>>,
+}
+
+impl RoutingLog {
+	/// Creates an empty log.
+	fn new() -> RoutingLog {
+		RoutingLog {
+			bits: Rc::new(RefCell::new(LogBits {
+				words: VecDeque::new(),
+				first: 0,
+				len: 0,
+			})),
+		}
+	}
+	
+	/// Returns the number of items logged so far.
+	pub fn len(&self) -> usize {
+		self.bits.borrow().len
+	}
+	
+	/// Returns `true` if no items have been logged yet.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl Debug for RoutingLog {
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("RoutingLog")
+			.field("len", &self.len())
+			.finish()
+	}
+}
+
+
+/// Classifier that logs the side of every item. Created by
+/// `Splittable::split_logged`.
+pub struct Logged<P> {
+	/// Predicate that chooses the side.
+	predicate: P,
+	/// Log the sides are written to.
+	log: RoutingLog,
+}
+
+impl<P> Logged<P> {
+	/// Wraps a predicate and returns the log it writes to.
+	pub(crate) fn new(predicate: P) -> (Logged<P>, RoutingLog) {
+		let log = RoutingLog::new();
+		let logged = Logged {
+			predicate,
+			log: log.clone(),
+		};
+		(logged, log)
+	}
+}
+
+impl<T, P> Classifier<T> for Logged<P> where
+	P: FnMut(&T) -> bool
+{
+	fn classify(&mut self, position: Option<usize>, item: T) -> Routed<T> {
+		// A `LoggedSplit` only takes items from the front, so they are logged
+		// in order
+		debug_assert_eq!(position, Some(self.log.len()));
+		let is_right = (self.predicate)(&item);
+		self.log.bits.borrow_mut().push(is_right);
+		if is_right {
+			Routed::Right(item)
+		} else {
+			Routed::Left(item)
+		}
+	}
+}
+
+impl<P> Debug for Logged<P> {
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("Logged")
+			.field("log", &self.log)
+			.finish()
+	}
+}
+
+
+/// One of a pair of iterators like `Split`, that logs the side of every
+/// item. Created by `Splittable::split_logged`.
+///
+/// Items can only be taken from the front, because the log records them in
+/// their original order.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct LoggedSplit<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{
+	/// Split that returns the items.
+	split: Split<I, Logged<P>>,
+}
+
+impl<I, P> LoggedSplit<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{
+	/// Wraps a split.
+	pub(crate) fn new(split: Split<I, Logged<P>>) -> LoggedSplit<I, P> {
+		LoggedSplit {
+			split,
+		}
+	}
+}
+
+impl<I, P> Iterator for LoggedSplit<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.split.next()
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.split.size_hint()
+	}
+}
+
+impl<I, P> FusedIterator for LoggedSplit<I, P> where
+	I: Iterator,
+	P: FnMut(&I::Item) -> bool
+{}
+
+impl<I, P> Debug for LoggedSplit<I, P> where
+	I: Iterator + Debug,
+	P: FnMut(&I::Item) -> bool
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("LoggedSplit")
+			.field("split", &self.split)
+			.finish()
+	}
+}
+
+
+/// Iterator that merges the two sides of a split back into the original
+/// order. Created by `merge_ordered`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeOrdered<L, R> where
+	L: Iterator,
+	R: Iterator<Item = L::Item>
+{
+	/// Iterator over the items that went left.
+	left: L,
+	/// Iterator over the items that went right.
+	right: R,
+	/// Sides of the items in their original order.
+	log: RoutingLog,
+	/// Position of the next item.
+	position: usize,
+	/// Item that has been taken ahead of its position, together with that
+	/// position.
+	stash: Option<(usize, L::Item)>,
+}
+
+impl<L, R> Iterator for MergeOrdered<L, R> where
+	L: Iterator,
+	R: Iterator<Item = L::Item>
+{
+	type Item = L::Item;
+	
+	fn next(&mut self) -> Option<L::Item> {
+		let position = self.position;
+		let is_right = self.log.bits.borrow().is_right(position);
+		
+		let next = match is_right {
+			Some(_) if self.stash.as_ref().map(|stash| stash.0) == Some(position) =>
+				self.stash.take().map(|(_, next)| next),
+			Some(true) => self.right.next(),
+			Some(false) => self.left.next(),
+			None => {
+				// The side of the next item isn't known yet. Taking an item
+				// from the left logs every item up to it; the ones before it
+				// went right.
+				match self.left.next() {
+					Some(next) => {
+						let stashed = self.log.len() - 1;
+						if stashed == position {
+							Some(next)
+						} else {
+							self.stash = Some((stashed, next));
+							self.right.next()
+						}
+					}
+					None => self.right.next(),
+				}
+			}
+		};
+		
+		if next.is_some() {
+			self.position += 1;
+			self.log.bits.borrow_mut().forget_before(self.position);
+		}
+		next
+	}
+}
+
+impl<L, R> Debug for MergeOrdered<L, R> where
+	L: Iterator + Debug,
+	R: Iterator<Item = L::Item> + Debug
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("MergeOrdered")
+			.field("left", &self.left)
+			.field("right", &self.right)
+			.field("position", &self.position)
+			.finish()
+	}
+}
+
+
+/// Merges the two sides of a split created by `Splittable::split_logged`
+/// back into the original order, using the routing log instead of sequence
+/// numbers attached to the items.
+///
+/// The sides may be transformed with adaptors like `map` that return exactly
+/// one item for every item, but must not have been advanced before.
+///
+/// # Example
+///
+/// ```
+/// use split_iter::{Splittable, merge_ordered};
+///
+/// let (even, odd, log) = (1..10).split_logged(|v| v % 2 == 1);
+/// let merged = merge_ordered(even, odd.map(|v| v * 10), log);
+///
+/// assert_eq!(merged.collect::<Vec<_>>(), [10,2,30,4,50,6,70,8,90]);
+/// ```
+pub fn merge_ordered<L, R>(left: L, right: R, log: RoutingLog)
+	-> MergeOrdered<L, R> where
+	L: Iterator,
+	R: Iterator<Item = L::Item>
+{
+	MergeOrdered {
+		left,
+		right,
+		log,
+		position: 0,
+		stash: None,
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use Splittable;
+	use super::merge_ordered;
+	
+	#[test]
+	fn restores_order() {
+		let (left, right, log) = (0..200).split_logged(|v| v % 7 < 3);
+		let merged = merge_ordered(left.map(|v| -v), right, log);
+		
+		let expected = (0..200)
+			.map(|v| if v % 7 < 3 { v } else { -v })
+			.collect::<Vec<_>>();
+		assert_eq!(merged.collect::<Vec<_>>(), expected);
+	}
+	
+	#[test]
+	fn one_side_only() {
+		let (left, right, log) = (0..5).split_logged(|_| true);
+		let merged = merge_ordered(left, right, log.clone());
+		
+		assert_eq!(merged.collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+		assert_eq!(log.len(), 5);
+	}
+	
+	#[test]
+	fn log_is_trimmed() {
+		let (left, right, log) = (0..1000).split_logged(|v| v % 2 == 0);
+		let mut merged = merge_ordered(left, right, log.clone());
+		
+		assert_eq!(merged.by_ref().take(900).count(), 900);
+		assert!(log.bits.borrow().words.len() <= 2);
+		assert_eq!(merged.collect::<Vec<_>>(), (900..1000).collect::<Vec<_>>());
+	}
+}
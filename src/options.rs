@@ -0,0 +1,160 @@
+/// What happens when an item has to be cached for one side, but that side
+/// already has the maximum number of items cached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+	/// Panic.
+	Panic,
+	/// Don't take more items from the inner iterator until the opposite side
+	/// has caught up. `try_next` returns `SplitError::CacheFull` in the
+	/// meantime, and `next` panics.
+	Error,
+	/// Discard the oldest cached item to make room for the new one.
+	DropOldest,
+	/// Discard the new item.
+	DropNewest,
+	/// Don't take more items from the inner iterator until the opposite side
+	/// has caught up, and block in the meantime. Only used by
+	/// `Splittable::split_blocking`; a `Split` can't wait, so
+	/// `Splittable::split_with` panics with this policy.
+	Stall,
+}
+
+
+/// Options for `Splittable::split_with`.
+///
+/// By default, there is no limit for the number of cached items.
+///
+/// # Example
+///
+/// ```
+/// use split_iter::{Splittable, SplitOptions, Overflow};
+///
+/// let options = SplitOptions::new()
+/// 	.max_cached(2)
+/// 	.on_overflow(Overflow::DropOldest);
+/// let (odd, even) = (1..10).split_with(options, |v| v % 2 == 0);
+///
+/// assert_eq!(odd.collect::<Vec<_>>(), [1,3,5,7,9]);
+/// assert_eq!(even.collect::<Vec<_>>(), [6,8]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SplitOptions {
+	/// Maximum number of cached items, if any.
+	pub(crate) max_cached: Option<usize>,
+	/// What happens if more items would have to be cached.
+	pub(crate) on_overflow: Overflow,
+}
+
+impl SplitOptions {
+	/// Creates the default options.
+	pub fn new() -> SplitOptions {
+		SplitOptions {
+			max_cached: None,
+			on_overflow: Overflow::Panic,
+		}
+	}
+	
+	/// Limits the number of items that are cached for the side that is
+	/// behind. If the splits are used from both ends, the limit applies to
+	/// each end separately.
+	///
+	/// With `Overflow::Error`, `max_cached` has to be at least 1, or no side
+	/// could ever get past an item for the opposite side.
+	/// `Splittable::split_with` panics otherwise.
+	pub fn max_cached(mut self, max_cached: usize) -> SplitOptions {
+		self.max_cached = Some(max_cached);
+		self
+	}
+	
+	/// Sets what happens if more than `max_cached` items would have to be
+	/// cached. Defaults to `Overflow::Panic`.
+	pub fn on_overflow(mut self, on_overflow: Overflow) -> SplitOptions {
+		self.on_overflow = on_overflow;
+		self
+	}
+}
+
+impl Default for SplitOptions {
+	fn default() -> SplitOptions {
+		SplitOptions::new()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use {Splittable, SplitOptions, Overflow, SplitError};
+	
+	fn options(on_overflow: Overflow) -> SplitOptions {
+		SplitOptions::new()
+			.max_cached(2)
+			.on_overflow(on_overflow)
+	}
+	
+	#[test]
+	fn unbounded_by_default() {
+		let (low, high) = (1..20).split_with(SplitOptions::new(), |v| v >= &10);
+		assert_eq!(high.collect::<Vec<_>>(), (10..20).collect::<Vec<_>>());
+		assert_eq!(low.collect::<Vec<_>>(), (1..10).collect::<Vec<_>>());
+	}
+	
+	#[test]
+	#[should_panic]
+	fn panic() {
+		let (_low, high) = (1..20).split_with(options(Overflow::Panic), |v| v >= &10);
+		high.for_each(drop);
+	}
+	
+	#[test]
+	fn panic_within_limit() {
+		let (odd, even) = (1..10).split_with(options(Overflow::Panic), |v| v % 2 == 0);
+		assert_eq!(odd.zip(even).collect::<Vec<_>>(), [(1,2),(3,4),(5,6),(7,8)]);
+	}
+	
+	#[test]
+	fn error() {
+		let (mut low, mut high) = (1..6).split_with(options(Overflow::Error), |v| v >= &3);
+		assert_eq!(high.try_next(), Err(SplitError::CacheFull));
+		assert_eq!(low.try_next(), Ok(Some(1)));
+		assert_eq!(high.try_next(), Ok(Some(3)));
+		assert_eq!(low.try_next(), Ok(Some(2)));
+		assert_eq!(low.try_next(), Err(SplitError::CacheFull));
+		assert_eq!(high.by_ref().collect::<Vec<_>>(), [4,5]);
+		assert_eq!(low.try_next(), Ok(None));
+	}
+	
+	#[test]
+	fn drop_oldest() {
+		let (low, high) = (1..10).split_with(options(Overflow::DropOldest), |v| v >= &5);
+		assert_eq!(high.collect::<Vec<_>>(), [5,6,7,8,9]);
+		assert_eq!(low.collect::<Vec<_>>(), [3,4]);
+	}
+	
+	#[test]
+	fn drop_newest() {
+		let (low, high) = (1..10).split_with(options(Overflow::DropNewest), |v| v >= &5);
+		assert_eq!(high.collect::<Vec<_>>(), [5,6,7,8,9]);
+		assert_eq!(low.collect::<Vec<_>>(), [1,2]);
+	}
+	
+	#[test]
+	#[should_panic(expected = "the cache for the opposite side is full")]
+	fn error_next_panics() {
+		let (_low, high) = (1..10).split_with(options(Overflow::Error), |v| v >= &5);
+		// A fused split must never return `None` before its last item
+		let _ = high.fuse().next();
+	}
+	
+	#[test]
+	#[should_panic(expected = "max_cached must be at least 1")]
+	fn error_without_cache() {
+		let options = SplitOptions::new().max_cached(0).on_overflow(Overflow::Error);
+		let _ = (1..10).split_with(options, |_| true);
+	}
+	
+	#[test]
+	#[should_panic(expected = "Overflow::Stall needs split_blocking")]
+	fn stall_is_rejected() {
+		let _ = (1..10).split_with(options(Overflow::Stall), |v| v >= &5);
+	}
+}
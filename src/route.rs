@@ -0,0 +1,243 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+use Split;
+
+
+/// Where an item goes. Returned by the function passed to
+/// `Splittable::split_route`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Route {
+	/// The item goes to the left iterator.
+	Left,
+	/// The item goes to the right iterator.
+	Right,
+	/// The item is cloned and goes to both iterators.
+	Both,
+	/// The item is discarded.
+	Drop,
+}
+
+
+/// An item after it has been classified.
+#[doc(hidden)]
+pub enum Routed<T> {
+	/// The item goes to the left side.
+	Left(T),
+	/// The item goes to the right side.
+	Right(T),
+	/// One copy of the item goes to each side.
+	Both(T, T),
+	/// The item goes to neither side.
+	Neither,
+	/// The item goes to the side that is taking items from the source.
+	Caller(T),
+	/// The item goes to the side that is taking items from the source, and
+	/// no more items are taken from the source afterwards.
+	Failure(T),
+}
+
+
+/// Decides which side of a split an item goes to.
+///
+/// Implemented for every predicate `FnMut(&T) -> bool`, which sends an item
+/// to the right side if it returns `true` and to the left side otherwise.
+pub trait Classifier<T> {
+	/// Classifies the item at the given position of the source, if the
+	/// position is known.
+	#[doc(hidden)]
+	fn classify(&mut self, position: Option<usize>, item: T) -> Routed<T>;
+}
+
+impl<T, F> Classifier<T> for F where
+	F: FnMut(&T) -> bool
+{
+	fn classify(&mut self, _: Option<usize>, item: T) -> Routed<T> {
+		if self(&item) {
+			Routed::Right(item)
+		} else {
+			Routed::Left(item)
+		}
+	}
+}
+
+
+/// Classifier that routes items according to a function that returns a
+/// `Route`. Created by `Splittable::split_route`.
+#[derive(Clone, Debug)]
+pub struct ByRoute<F>(pub(crate) F);
+
+impl<T, F> Classifier<T> for ByRoute<F> where
+	T: Clone,
+	F: FnMut(&T) -> Route
+{
+	fn classify(&mut self, _: Option<usize>, item: T) -> Routed<T> {
+		match (self.0)(&item) {
+			Route::Left => Routed::Left(item),
+			Route::Right => Routed::Right(item),
+			Route::Both => Routed::Both(item.clone(), item),
+			Route::Drop => Routed::Neither,
+		}
+	}
+}
+
+
+/// Classifier that passes the position of each item in the source to the
+/// predicate. Created by `Splittable::split_indexed`.
+#[derive(Clone, Debug)]
+pub struct Indexed<F>(pub(crate) F);
+
+impl<T, F> Classifier<T> for Indexed<F> where
+	F: FnMut(usize, &T) -> bool
+{
+	fn classify(&mut self, position: Option<usize>, item: T) -> Routed<T> {
+		let position = position.expect("the inner iterator reported a wrong length");
+		if (self.0)(position, &item) {
+			Routed::Right(item)
+		} else {
+			Routed::Left(item)
+		}
+	}
+}
+
+
+/// One of a pair of iterators like `Split`, whose predicate also gets the
+/// position of every item in the inner iterator. Created by
+/// `Splittable::split_indexed`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitIndexed<I, F> where
+	I: Iterator,
+	F: FnMut(usize, &I::Item) -> bool
+{
+	/// Split that returns the items.
+	split: Split<I, Indexed<F>>,
+}
+
+impl<I, F> SplitIndexed<I, F> where
+	I: Iterator,
+	F: FnMut(usize, &I::Item) -> bool
+{
+	/// Wraps a split.
+	pub(crate) fn new(split: Split<I, Indexed<F>>) -> SplitIndexed<I, F> {
+		SplitIndexed {
+			split,
+		}
+	}
+	
+	/// Returns the position in the inner iterator of the item that was
+	/// returned last, counted from zero, like `Split::source_index_of_last`.
+	pub fn source_index_of_last(&self) -> Option<usize> {
+		self.split.source_index_of_last()
+	}
+}
+
+impl<I, F> Iterator for SplitIndexed<I, F> where
+	I: Iterator,
+	F: FnMut(usize, &I::Item) -> bool
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.split.next()
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.split.size_hint()
+	}
+}
+
+impl<I, F> DoubleEndedIterator for SplitIndexed<I, F> where
+	I: DoubleEndedIterator + ExactSizeIterator,
+	F: FnMut(usize, &I::Item) -> bool
+{
+	fn next_back(&mut self) -> Option<I::Item> {
+		self.split.next_back()
+	}
+}
+
+impl<I, F> FusedIterator for SplitIndexed<I, F> where
+	I: Iterator,
+	F: FnMut(usize, &I::Item) -> bool
+{}
+
+impl<I, F> Debug for SplitIndexed<I, F> where
+	I: Iterator + Debug,
+	F: FnMut(usize, &I::Item) -> bool
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitIndexed")
+			.field("split", &self.split)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use {Splittable, Route};
+	
+	fn route(v: &i32) -> Route {
+		match v % 4 {
+			0 => Route::Left,
+			1 => Route::Right,
+			2 => Route::Both,
+			_ => Route::Drop,
+		}
+	}
+	
+	#[test]
+	fn routes_items() {
+		let (left, right) = (0..12).split_route(route);
+		
+		assert_eq!(left.collect::<Vec<_>>(), [0, 2, 4, 6, 8, 10]);
+		assert_eq!(right.collect::<Vec<_>>(), [1, 2, 5, 6, 9, 10]);
+	}
+	
+	#[test]
+	fn interleaved_and_double_ended() {
+		let (mut left, mut right) = (0..12).split_route(route);
+		
+		assert_eq!(right.next_back(), Some(10));
+		assert_eq!(left.next(), Some(0));
+		assert_eq!(left.next_back(), Some(10));
+		assert_eq!(right.next(), Some(1));
+		assert_eq!(left.next(), Some(2));
+		assert_eq!(right.collect::<Vec<_>>(), [2, 5, 6, 9]);
+		assert_eq!(left.collect::<Vec<_>>(), [4, 6, 8]);
+	}
+	
+	#[test]
+	fn both_with_dropped_side() {
+		let (left, right) = vec![String::from("a"), String::from("b")]
+			.into_iter()
+			.split_route(|_| Route::Both);
+		drop(left);
+		
+		assert_eq!(right.collect::<Vec<_>>(), ["a", "b"]);
+	}
+	
+	#[test]
+	fn indexed() {
+		let (mut rest, mut sample) = (10..20)
+			.split_indexed(|index, _| index % 3 == 0);
+		
+		assert_eq!(sample.next(), Some(10));
+		assert_eq!(sample.next_back(), Some(19));
+		assert_eq!(sample.source_index_of_last(), Some(9));
+		assert_eq!(rest.next_back(), Some(18));
+		assert_eq!(rest.source_index_of_last(), Some(8));
+		assert_eq!(rest.collect::<Vec<_>>(), [11, 12, 14, 15, 17]);
+		assert_eq!(sample.collect::<Vec<_>>(), [13, 16]);
+	}
+	
+	#[test]
+	fn indexed_without_length() {
+		let (first, rest) = (0..10).filter(|v| v % 3 != 0)
+			.split_indexed(|index, _| index > 0);
+		
+		assert_eq!(rest.collect::<Vec<_>>(), [2, 4, 5, 7, 8]);
+		assert_eq!(first.collect::<Vec<_>>(), [1]);
+	}
+}
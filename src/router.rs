@@ -0,0 +1,188 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+
+use SplitN;
+
+
+/// Rule of a `Router`, which may borrow from the lifetime `'a`.
+type Rule<'a, T> = Box<dyn FnMut(&T) -> bool + 'a>;
+
+/// Function that chooses the iterator of a `RoutingTable` an item goes to.
+pub type RouteIndex<'a, T> = Box<dyn FnMut(&T) -> usize + 'a>;
+
+/// One of the iterators created by `Splittable::split_router`.
+pub type RouterSplit<'a, I> = SplitN<I, RouteIndex<'a, <I as Iterator>::Item>>;
+
+
+/// Builder for an ordered list of rules. Every item goes to the iterator of
+/// the first rule that matches it, or to the fallback iterator if no rule
+/// matches.
+///
+/// # Example
+///
+/// ```
+/// use split_iter::{Splittable, Router};
+///
+/// let router = Router::new()
+/// 	.route(|v: &i32| v % 15 == 0)
+/// 	.route(|v: &i32| v % 5 == 0)
+/// 	.route(|v: &i32| v % 3 == 0)
+/// 	.fallback();
+///
+/// let mut routes = (1..16).split_router(router).into_iter();
+/// let fizzbuzz = routes.next().unwrap();
+/// let buzz = routes.next().unwrap();
+/// let fizz = routes.next().unwrap();
+/// let other = routes.next().unwrap();
+///
+/// assert_eq!(fizz.collect::<Vec<_>>(), [3,6,9,12]);
+/// assert_eq!(buzz.collect::<Vec<_>>(), [5,10]);
+/// assert_eq!(fizzbuzz.collect::<Vec<_>>(), [15]);
+/// assert_eq!(other.collect::<Vec<_>>(), [1,2,4,7,8,11,13,14]);
+/// ```
+pub struct Router<'a, T> {
+	/// Rules in the order they are tried.
+	rules: Vec<Rule<'a, T>>,
+}
+
+impl<'a, T> Router<'a, T> {
+	/// Creates a router without rules.
+	pub fn new() -> Router<'a, T> {
+		Router {
+			rules: Vec::new(),
+		}
+	}
+	
+	/// Adds a rule that is tried after all previous rules.
+	pub fn route<P>(mut self, predicate: P) -> Router<'a, T> where
+		P: FnMut(&T) -> bool + 'a
+	{
+		self.rules.push(Box::new(predicate));
+		self
+	}
+	
+	/// Finishes the router. Items that match no rule go to the last
+	/// iterator.
+	pub fn fallback(self) -> RoutingTable<'a, T> {
+		RoutingTable {
+			rules: self.rules,
+		}
+	}
+}
+
+impl<'a, T> Default for Router<'a, T> {
+	fn default() -> Router<'a, T> {
+		Router::new()
+	}
+}
+
+impl<'a, T> Debug for Router<'a, T> {
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("Router")
+			.field("rules", &self.rules.len())
+			.finish()
+	}
+}
+
+
+/// A finished `Router` with a fallback. Passed to `Splittable::split_router`.
+pub struct RoutingTable<'a, T> {
+	/// Rules in the order they are tried.
+	rules: Vec<Rule<'a, T>>,
+}
+
+impl<'a, T> RoutingTable<'a, T> {
+	/// Returns the number of iterators the table splits into, which is the
+	/// number of rules plus one for the fallback.
+	pub fn len(&self) -> usize {
+		self.rules.len() + 1
+	}
+	
+	/// Always returns `false`, because there is at least the fallback.
+	pub fn is_empty(&self) -> bool {
+		false
+	}
+	
+	/// Turns the table into a function that returns the index of the first
+	/// matching rule, or of the fallback.
+	pub(crate) fn into_index(self) -> RouteIndex<'a, T> where
+		T: 'a
+	{
+		let mut rules = self.rules;
+		Box::new(move |item| {
+			let fallback = rules.len();
+			rules.iter_mut()
+				.position(|rule| rule(item))
+				.unwrap_or(fallback)
+		})
+	}
+}
+
+impl<'a, T> Debug for RoutingTable<'a, T> {
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("RoutingTable")
+			.field("rules", &self.rules.len())
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::rc::Rc;
+	use std::cell::Cell;
+	use {Splittable, Router};
+	
+	#[test]
+	fn fallback_only() {
+		let routes = (0..5).split_router(Router::new().fallback());
+		
+		assert_eq!(routes.len(), 1);
+		assert_eq!(routes.into_iter().next().unwrap().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+	}
+	
+	#[test]
+	fn rules_stop_at_first_match() {
+		let calls = Rc::new(Cell::new(0));
+		let first_calls = calls.clone();
+		let second_calls = calls.clone();
+		let router = Router::new()
+			.route(move |v: &i32| {
+				first_calls.set(first_calls.get() + 1);
+				*v < 3
+			})
+			.route(move |v: &i32| {
+				second_calls.set(second_calls.get() + 1);
+				*v < 6
+			})
+			.fallback();
+		
+		let mut routes = (0..8).split_router(router);
+		let rest = routes.pop().unwrap();
+		let middle = routes.pop().unwrap();
+		let low = routes.pop().unwrap();
+		
+		assert_eq!(rest.collect::<Vec<_>>(), [6, 7]);
+		assert_eq!(low.collect::<Vec<_>>(), [0, 1, 2]);
+		assert_eq!(middle.collect::<Vec<_>>(), [3, 4, 5]);
+		// 8 calls of the first rule, 5 of the second
+		assert_eq!(calls.get(), 13);
+	}
+	
+	#[test]
+	fn borrowed_items_and_rules() {
+		let values = [1, 2, 3, 4];
+		let limit = 2;
+		let router = Router::new()
+			.route(|v: &&i32| **v > limit)
+			.fallback();
+		
+		let mut routes = values.iter().split_router(router);
+		let small = routes.pop().unwrap();
+		let large = routes.pop().unwrap();
+		
+		assert_eq!(large.collect::<Vec<_>>(), [&3, &4]);
+		assert_eq!(small.collect::<Vec<_>>(), [&1, &2]);
+	}
+}
@@ -0,0 +1,228 @@
+use std::rc::Rc;
+use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+
+/// Shared inner state for a group of `SplitN`s.
+struct SharedSplitNState<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	/// Inner iterator.
+	iter: I,
+	/// Function that chooses the index of the iterator an item goes to.
+	index: F,
+	/// One cache per iterator for items that have been skipped by the
+	/// other iterators.
+	caches: Vec<VecDeque<I::Item>>,
+	/// Does the iterator with the given index still exist?
+	is_alive: Vec<bool>,
+	/// Has the inner iterator returned `None`?
+	is_exhausted: bool,
+}
+
+impl<I, F> SharedSplitNState<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	/// Creates shared inner state for `count` iterators.
+	fn new(iter: I, index: F, count: usize) -> SharedSplitNState<I, F> {
+		SharedSplitNState {
+			iter,
+			index,
+			caches: (0..count).map(|_| VecDeque::new()).collect(),
+			is_alive: vec![true; count],
+			is_exhausted: false,
+		}
+	}
+	
+	/// Returns next item for the iterator with the given index.
+	fn next(&mut self, index: usize) -> Option<I::Item> {
+		if let Some(next) = self.caches[index].pop_front() {
+			return Some(next);
+		}
+		
+		while !self.is_exhausted {
+			match self.iter.next() {
+				Some(next) => {
+					let next_index = (self.index)(&next);
+					assert!(
+						next_index < self.caches.len(),
+						"split_n index {} out of range for {} iterators",
+						next_index,
+						self.caches.len()
+					);
+					
+					if next_index == index {
+						return Some(next);
+					} else if self.is_alive[next_index] {
+						// Fill cache with elements for other iterators
+						self.caches[next_index].push_back(next);
+					}
+				},
+				None => self.is_exhausted = true,
+			}
+		}
+		
+		None
+	}
+	
+	/// Returns the bounds on the remaining length of the iterator with the
+	/// given index.
+	fn size_hint(&self, index: usize) -> (usize, Option<usize>) {
+		let cached = self.caches[index].len();
+		if self.is_exhausted {
+			(cached, Some(cached))
+		} else {
+			let (_, upper) = self.iter.size_hint();
+			(cached, upper.and_then(|upper| upper.checked_add(cached)))
+		}
+	}
+	
+	/// Marks the iterator with the given index as dropped, so no more items
+	/// are cached for it.
+	fn drop_index(&mut self, index: usize) {
+		self.is_alive[index] = false;
+		self.caches[index] = VecDeque::new();
+	}
+}
+
+
+/// One of a fixed number of iterators that split an iterator by index.
+/// Created by `Splittable::split_n` and `Splittable::split_router`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitN<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	/// Shared state with the other iterators.
+	shared: Rc<RefCell<SharedSplitNState<I, F>>>,
+	/// Index of the items returned by this iterator.
+	index: usize,
+}
+
+impl<I, F> SplitN<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	/// Returns the index of the items returned by this iterator.
+	pub fn index(&self) -> usize {
+		self.index
+	}
+}
+
+impl<I, F> Iterator for SplitN<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.shared.borrow_mut().next(self.index)
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.shared.borrow().size_hint(self.index)
+	}
+}
+
+impl<I, F> FusedIterator for SplitN<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{}
+
+impl<I, F> Drop for SplitN<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	fn drop(&mut self) {
+		if let Ok(mut shared) = self.shared.try_borrow_mut() {
+			shared.drop_index(self.index);
+		}
+	}
+}
+
+impl<I, F> Debug for SplitN<I, F> where
+	I: Iterator + Debug,
+	F: FnMut(&I::Item) -> usize
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitN")
+			.field("index", &self.index)
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+
+/// Creates the shared state for `count` iterators, and returns a function
+/// that creates the iterator with a given index.
+fn splits<I, F>(iter: I, index: F, count: usize)
+	-> impl FnMut(usize) -> SplitN<I, F> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	let shared = Rc::new(
+		RefCell::new(
+			SharedSplitNState::new(iter, index, count)
+		)
+	);
+	
+	move |index| SplitN {
+		shared: shared.clone(),
+		index,
+	}
+}
+
+/// Creates `N` iterators that share one inner iterator.
+pub(crate) fn split_n<const N: usize, I, F>(iter: I, index: F)
+	-> [SplitN<I, F>; N] where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	::std::array::from_fn(splits(iter, index, N))
+}
+
+/// Creates `count` iterators that share one inner iterator.
+pub(crate) fn split_count<I, F>(iter: I, index: F, count: usize)
+	-> Vec<SplitN<I, F>> where
+	I: Iterator,
+	F: FnMut(&I::Item) -> usize
+{
+	(0..count).map(splits(iter, index, count)).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use Splittable;
+	
+	#[test]
+	fn splits_by_index() {
+		let [zero, one, two] = (0..10).split_n(|v| v % 3);
+		
+		assert_eq!(two.collect::<Vec<_>>(), [2, 5, 8]);
+		assert_eq!(zero.collect::<Vec<_>>(), [0, 3, 6, 9]);
+		assert_eq!(one.collect::<Vec<_>>(), [1, 4, 7]);
+	}
+	
+	#[test]
+	fn dropped_iterator_is_not_cached() {
+		let [low, high] = (0..10).split_n(|&v| if v < 5 { 0 } else { 1 });
+		drop(low);
+		
+		assert_eq!(high.size_hint(), (0, Some(10)));
+		assert_eq!(high.collect::<Vec<_>>(), [5, 6, 7, 8, 9]);
+	}
+	
+	#[test]
+	#[should_panic(expected = "split_n index 3 out of range for 3 iterators")]
+	fn out_of_range_panics() {
+		let [mut zero, _, _] = (0..10).split_n(|v| v % 4);
+		zero.by_ref().for_each(drop);
+	}
+}
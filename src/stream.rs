@@ -0,0 +1,354 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+
+use futures::Stream;
+
+use {Sides, SendOrphans, SplitOptions, Classifier};
+
+
+/// Shared inner state for two `SplitStream`s.
+struct SharedStreamState<S, P> where
+	S: Stream + Unpin,
+	P: Classifier<S::Item>
+{
+	/// Inner stream.
+	stream: S,
+	/// Routing and caching state of both `SplitStream`s.
+	sides: Sides<S::Item, P, SendOrphans<S::Item>>,
+	/// Has the inner stream ended?
+	is_done: bool,
+	/// Number of items taken from the inner stream.
+	position: usize,
+	/// Waker of the task that waits for the left stream.
+	left_waker: Option<Waker>,
+	/// Waker of the task that waits for the right stream.
+	right_waker: Option<Waker>,
+}
+
+impl<S, P> SharedStreamState<S, P> where
+	S: Stream + Unpin,
+	P: Classifier<S::Item>
+{
+	/// Polls for the next item of the given `SplitStream`.
+	fn poll_next(&mut self, is_right: bool, cx: &mut Context)
+		-> Poll<Option<S::Item>>
+	{
+		// Use cache for correct side
+		if let Some((_, next)) = self.sides.take(is_right, false) {
+			return Poll::Ready(Some(next));
+		}
+		
+		// From inner stream
+		let mut routed_away = false;
+		let result = loop {
+			if self.is_done {
+				break None;
+			}
+			
+			match Pin::new(&mut self.stream).poll_next(cx) {
+				Poll::Ready(Some(next)) => {
+					let position = Some(self.position);
+					self.position += 1;
+					let routed = self.sides.route(is_right, false, position, next);
+					match routed {
+						Some(next) => break Some(next),
+						None => routed_away = true,
+					}
+				}
+				Poll::Ready(None) => {
+					self.is_done = true;
+				}
+				Poll::Pending => {
+					*self.waker(is_right) = Some(cx.waker().clone());
+					
+					// The opposite stream might wait for an item that has
+					// been cached by this poll. Waking it otherwise would
+					// make both tasks wake each other forever.
+					if routed_away {
+						self.wake(!is_right);
+					}
+					return Poll::Pending;
+				}
+			}
+		};
+		
+		// The opposite stream might wait for an item that has been cached
+		// now, or for a wake-up from the inner stream that only this task
+		// is registered for.
+		self.wake(!is_right);
+		
+		Poll::Ready(result)
+	}
+	
+	/// Returns the waker slot for the given `SplitStream`.
+	fn waker(&mut self, is_right: bool) -> &mut Option<Waker> {
+		if is_right {
+			&mut self.right_waker
+		} else {
+			&mut self.left_waker
+		}
+	}
+	
+	/// Wakes the task waiting for the given `SplitStream`, if there is one.
+	fn wake(&mut self, is_right: bool) {
+		if let Some(waker) = self.waker(is_right).take() {
+			waker.wake();
+		}
+	}
+}
+
+
+/// One of a pair of streams. One returns the items for which the predicate
+/// returns `false`, the other one returns the items for which the predicate
+/// returns `true`.
+///
+/// When one stream takes an item from the inner stream that belongs to the
+/// other one, the task waiting for the other stream is woken.
+///
+/// # Example
+///
+/// ```
+/// extern crate futures;
+/// extern crate split_iter;
+///
+/// use futures::executor::block_on;
+/// use futures::stream::{self, StreamExt};
+/// use split_iter::StreamSplittable;
+///
+/// fn main() {
+/// 	let (odd, even) = StreamSplittable::split(
+/// 		stream::iter(1..10),
+/// 		|v| v % 2 == 0,
+/// 	);
+///
+/// 	assert_eq!(block_on(odd.collect::<Vec<_>>()), [1,3,5,7,9]);
+/// 	assert_eq!(block_on(even.collect::<Vec<_>>()), [2,4,6,8]);
+/// }
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct SplitStream<S, P> where
+	S: Stream + Unpin,
+	P: Classifier<S::Item>
+{
+	/// Shared state with the opposite stream.
+	shared: Arc<Mutex<SharedStreamState<S, P>>>,
+	/// Is the stream the right one or the left one?
+	is_right: bool,
+}
+
+impl<S, P> SplitStream<S, P> where
+	S: Stream + Unpin,
+	P: Classifier<S::Item>
+{
+	/// Locks the shared state.
+	///
+	/// # Panics
+	///
+	/// Panics if the predicate or the inner stream panicked while the
+	/// opposite stream was using them.
+	fn lock(&self) -> MutexGuard<'_, SharedStreamState<S, P>> {
+		self.shared.lock()
+			.expect("the opposite SplitStream panicked")
+	}
+}
+
+impl<S, P> Stream for SplitStream<S, P> where
+	S: Stream + Unpin,
+	P: Classifier<S::Item>
+{
+	type Item = S::Item;
+	
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context)
+		-> Poll<Option<S::Item>>
+	{
+		self.lock().poll_next(self.is_right, cx)
+	}
+}
+
+impl<S, P> Drop for SplitStream<S, P> where
+	S: Stream + Unpin,
+	P: Classifier<S::Item>
+{
+	fn drop(&mut self) {
+		// Don't panic while unwinding from a panic in the opposite stream
+		if let Ok(mut state) = self.shared.lock() {
+			state.sides.drop_side(self.is_right);
+			*state.waker(self.is_right) = None;
+			
+			// The opposite stream might wait for a wake-up from the inner
+			// stream that only this task is registered for.
+			state.wake(!self.is_right);
+		}
+	}
+}
+
+impl<S, P> Debug for SplitStream<S, P> where
+	S: Stream + Unpin + Debug,
+	P: Classifier<S::Item>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitStream")
+			.field("stream", &self.lock().stream)
+			.finish()
+	}
+}
+
+
+/// Provides a stream adaptor method that splits a stream into two streams
+/// according to a predicate.
+pub trait StreamSplittable<S> where
+	S: Stream + Unpin
+{
+	/// Splits the stream. The left stream returns all items for which the
+	/// `predicate` returns `false`. The right stream returns all items
+	/// for which the `predicate` returns `true`.
+	///
+	/// If `futures::StreamExt` is in scope, this method has to be called as
+	/// `StreamSplittable::split(stream, predicate)`.
+	fn split<P>(self, predicate: P) -> (SplitStream<S, P>, SplitStream<S, P>)
+		where P: FnMut(&S::Item) -> bool;
+}
+
+impl<S> StreamSplittable<S> for S where
+	S: Stream + Unpin
+{
+	fn split<P>(self, predicate: P) -> (SplitStream<S, P>, SplitStream<S, P>)
+		where P: FnMut(&S::Item) -> bool
+	{
+		let shared = Arc::new(
+			Mutex::new(
+				SharedStreamState {
+					stream: self,
+					sides: Sides::new(predicate, SplitOptions::new()),
+					is_done: false,
+					position: 0,
+					left_waker: None,
+					right_waker: None,
+				}
+			)
+		);
+		
+		let left = SplitStream {
+			shared: shared.clone(),
+			is_right: false,
+		};
+		
+		let right = SplitStream {
+			shared,
+			is_right: true,
+		};
+		
+		(left, right)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::thread;
+	use std::time::Duration;
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicBool, Ordering};
+	use std::task::{Context, Poll, Wake, Waker};
+	use futures::channel::mpsc;
+	use futures::executor::block_on;
+	use futures::future;
+	use futures::stream::{self, StreamExt};
+	use StreamSplittable;
+	
+	/// Waker that remembers whether it has been woken.
+	struct Flag(AtomicBool);
+	
+	impl Flag {
+		fn new() -> Arc<Flag> {
+			Arc::new(Flag(AtomicBool::new(false)))
+		}
+		
+		fn is_set(&self) -> bool {
+			self.0.load(Ordering::SeqCst)
+		}
+	}
+	
+	impl Wake for Flag {
+		fn wake(self: Arc<Flag>) {
+			self.0.store(true, Ordering::SeqCst);
+		}
+	}
+	
+	#[test]
+	fn it_works() {
+		let (low, high) = StreamSplittable::split(stream::iter(1..20), |v| v >= &10);
+		assert_eq!(block_on(high.collect::<Vec<_>>()), (10..20).collect::<Vec<_>>());
+		assert_eq!(block_on(low.collect::<Vec<_>>()), (1..10).collect::<Vec<_>>());
+	}
+	
+	#[test]
+	fn wakes_opposite_side() {
+		let (sender, receiver) = mpsc::unbounded();
+		let producer = thread::spawn(move || {
+			for v in 0..50 {
+				sender.unbounded_send(v).unwrap();
+				thread::sleep(Duration::from_millis(1));
+			}
+		});
+		
+		let (odd, even) = StreamSplittable::split(receiver, |v| v % 2 == 0);
+		let (odd, even) = block_on(future::join(
+			odd.collect::<Vec<_>>(),
+			even.collect::<Vec<_>>(),
+		));
+		producer.join().unwrap();
+		
+		assert_eq!(odd, (0..50).filter(|v| v % 2 == 1).collect::<Vec<_>>());
+		assert_eq!(even, (0..50).filter(|v| v % 2 == 0).collect::<Vec<_>>());
+	}
+	
+	#[test]
+	fn dropped_side_wakes_opposite_side() {
+		let (sender, receiver) = mpsc::unbounded();
+		let producer = thread::spawn(move || {
+			for v in 0..20 {
+				sender.unbounded_send(v).unwrap();
+				thread::sleep(Duration::from_millis(1));
+			}
+		});
+		
+		let (odd, even) = StreamSplittable::split(receiver, |v| v % 2 == 0);
+		let even = even.take(3).collect::<Vec<_>>();
+		let (odd, even) = block_on(future::join(odd.collect::<Vec<_>>(), even));
+		producer.join().unwrap();
+		
+		assert_eq!(odd, (0..20).filter(|v| v % 2 == 1).collect::<Vec<_>>());
+		assert_eq!(even, [0, 2, 4]);
+	}
+	
+	#[test]
+	fn cached_item_wakes_opposite_task() {
+		let (sender, receiver) = mpsc::unbounded();
+		let (mut odd, mut even) = StreamSplittable::split(receiver, |v| v % 2 == 0);
+		
+		let odd_flag = Flag::new();
+		let odd_waker = Waker::from(odd_flag.clone());
+		let mut odd_cx = Context::from_waker(&odd_waker);
+		let even_flag = Flag::new();
+		let even_waker = Waker::from(even_flag.clone());
+		let mut even_cx = Context::from_waker(&even_waker);
+		
+		assert_eq!(odd.poll_next_unpin(&mut odd_cx), Poll::Pending);
+		assert_eq!(even.poll_next_unpin(&mut even_cx), Poll::Pending);
+		
+		// The inner stream only wakes the even task, which caches the item
+		// for the odd one
+		sender.unbounded_send(1).unwrap();
+		assert!(even_flag.is_set());
+		assert!(!odd_flag.is_set());
+		assert_eq!(even.poll_next_unpin(&mut even_cx), Poll::Pending);
+		assert!(odd_flag.is_set());
+		
+		assert_eq!(odd.poll_next_unpin(&mut odd_cx), Poll::Ready(Some(1)));
+	}
+}
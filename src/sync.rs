@@ -0,0 +1,175 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+use {SharedSplitState, SharedSendState, SplitOptions, Classifier};
+
+
+/// One of a pair of iterators that can be sent to different threads.
+/// Works like `Split`, but the state shared with the opposite iterator is
+/// guarded by a mutex.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use split_iter::Splittable;
+///
+/// let (odd, even) = (1..10).split_sync(|v| v % 2 == 0);
+///
+/// let even = thread::spawn(move || even.collect::<Vec<_>>());
+///
+/// assert_eq!(odd.collect::<Vec<_>>(), [1,3,5,7,9]);
+/// assert_eq!(even.join().unwrap(), [2,4,6,8]);
+/// ```
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SyncSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// Shared state with the opposite iterator.
+	shared: Arc<Mutex<SharedSendState<I, P>>>,
+	/// Is the iterator the right one or the left one?
+	is_right: bool,
+}
+
+impl<I, P> SyncSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	/// Creates a pair of `SyncSplit`s.
+	pub(crate) fn new(iter: I, predicate: P) -> (SyncSplit<I, P>, SyncSplit<I, P>) {
+		let shared = Arc::new(
+			Mutex::new(
+				SharedSplitState::new(iter, predicate, SplitOptions::new())
+			)
+		);
+		
+		let left = SyncSplit {
+			shared: shared.clone(),
+			is_right: false,
+		};
+		
+		let right = SyncSplit {
+			shared,
+			is_right: true,
+		};
+		
+		(left, right)
+	}
+	
+	/// Drops the iterator, but passes every item that it would have returned
+	/// to `sink` instead of discarding it. See `Split::drop_into`.
+	pub fn drop_into<F>(self, sink: F) where
+		F: FnMut(I::Item) + Send + 'static
+	{
+		self.lock().drop_side_into(self.is_right, Box::new(sink));
+	}
+	
+	/// Locks the shared state.
+	///
+	/// # Panics
+	///
+	/// Panics if the predicate or the inner iterator panicked while the
+	/// opposite iterator was using them.
+	fn lock<'a>(&'a self) -> MutexGuard<'a, SharedSendState<I, P>> {
+		self.shared.lock()
+			.expect("the opposite SyncSplit panicked")
+	}
+}
+
+impl<I, P> Iterator for SyncSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.lock().next(self.is_right)
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.lock().size_hint(self.is_right)
+	}
+}
+
+impl<I, P> DoubleEndedIterator for SyncSplit<I, P> where
+	I: DoubleEndedIterator,
+	P: Classifier<I::Item>
+{
+	fn next_back(&mut self) -> Option<I::Item> {
+		self.lock().next_back(self.is_right)
+	}
+}
+
+impl<I, P> FusedIterator for SyncSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{}
+
+impl<I, P> Drop for SyncSplit<I, P> where
+	I: Iterator,
+	P: Classifier<I::Item>
+{
+	fn drop(&mut self) {
+		// Don't panic while unwinding from a panic in the opposite iterator
+		if let Ok(mut state) = self.shared.lock() {
+			state.drop_side(self.is_right);
+		}
+	}
+}
+
+impl<I, P> Debug for SyncSplit<I, P> where
+	I: Iterator + Debug,
+	P: Classifier<I::Item>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SyncSplit")
+			.field("iter", &self.lock().iter)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::thread;
+	use std::sync::mpsc;
+	use Splittable;
+	
+	fn is_send<T: Send>(_: &T) {}
+	
+	#[test]
+	fn halves_are_send() {
+		let (left, right) = (1..10).split_sync(|v| v % 2 == 0);
+		is_send(&left);
+		is_send(&right);
+	}
+	
+	#[test]
+	fn concurrent_matches_single_threaded() {
+		let predicate = |v: &u32| v % 7 < 3;
+		let (left, right) = (0..10_000).split_sync(predicate);
+		
+		let left = thread::spawn(move || left.collect::<Vec<_>>());
+		let right = thread::spawn(move || right.collect::<Vec<_>>());
+		
+		let (expected_left, expected_right) = (0..10_000).split(predicate);
+		assert_eq!(left.join().unwrap(), expected_left.collect::<Vec<_>>());
+		assert_eq!(right.join().unwrap(), expected_right.collect::<Vec<_>>());
+	}
+	
+	#[test]
+	fn dropped_side_into_sink() {
+		let (sender, receiver) = mpsc::channel();
+		let (rest, thirds) = (1..10).split_sync(|v| v % 3 == 0);
+		
+		thirds.drop_into(move |v| sender.send(v).unwrap());
+		
+		let rest = thread::spawn(move || rest.collect::<Vec<_>>());
+		assert_eq!(rest.join().unwrap(), [1,2,4,5,7,8]);
+		assert_eq!(receiver.iter().collect::<Vec<_>>(), [3,6,9]);
+	}
+}
@@ -0,0 +1,268 @@
+use std::rc::Rc;
+use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+
+/// Shared inner state for a group of `Tee`s.
+struct SharedTeeState<I> where
+	I: Iterator,
+	I::Item: Clone
+{
+	/// Inner iterator.
+	iter: I,
+	/// Items that have been taken from the inner iterator, but not yet by
+	/// all `Tee`s.
+	buffer: VecDeque<I::Item>,
+	/// Source position of the first item in `buffer`.
+	start: usize,
+	/// Source position of the next item for each `Tee`, or `None` if the
+	/// `Tee` has been dropped.
+	positions: Vec<Option<usize>>,
+	/// Has the inner iterator returned `None`?
+	is_exhausted: bool,
+}
+
+impl<I> SharedTeeState<I> where
+	I: Iterator,
+	I::Item: Clone
+{
+	/// Creates shared inner state for `count` `Tee`s.
+	fn new(iter: I, count: usize) -> SharedTeeState<I> {
+		SharedTeeState {
+			iter,
+			buffer: VecDeque::new(),
+			start: 0,
+			positions: vec![Some(0); count],
+			is_exhausted: false,
+		}
+	}
+	
+	/// Returns next item for the given `Tee`.
+	fn next(&mut self, id: usize) -> Option<I::Item> {
+		let position = self.positions[id]
+			.expect("a dropped Tee has been used");
+		
+		let next = if position < self.start + self.buffer.len() {
+			// From buffer
+			if position == self.start && self.count_at(self.start) == 1 {
+				// No other `Tee` needs it anymore
+				self.start += 1;
+				self.buffer.pop_front()
+			} else {
+				Some(self.buffer[position - self.start].clone())
+			}
+		} else {
+			// From inner iterator
+			if self.is_exhausted {
+				return None;
+			}
+			
+			match self.iter.next() {
+				Some(next) => {
+					if self.positions.iter().flatten().count() > 1 {
+						self.buffer.push_back(next.clone());
+					}
+					Some(next)
+				}
+				None => {
+					self.is_exhausted = true;
+					return None;
+				}
+			}
+		};
+		
+		self.positions[id] = Some(position + 1);
+		self.trim();
+		next
+	}
+	
+	/// Returns the bounds on the remaining number of items for the given
+	/// `Tee`.
+	fn size_hint(&self, id: usize) -> (usize, Option<usize>) {
+		let buffered = match self.positions[id] {
+			Some(position) => self.start + self.buffer.len() - position,
+			None => 0,
+		};
+		if self.is_exhausted {
+			return (buffered, Some(buffered));
+		}
+		
+		let (lower, upper) = self.iter.size_hint();
+		(
+			lower.saturating_add(buffered),
+			upper.and_then(|upper| upper.checked_add(buffered)),
+		)
+	}
+	
+	/// Marks the given `Tee` as dropped.
+	fn drop_tee(&mut self, id: usize) {
+		self.positions[id] = None;
+		self.trim();
+	}
+	
+	/// Returns the number of live `Tee`s at the given position.
+	fn count_at(&self, position: usize) -> usize {
+		self.positions.iter()
+			.filter(|&&other| other == Some(position))
+			.count()
+	}
+	
+	/// Frees all buffered items that every live `Tee` has passed.
+	fn trim(&mut self) {
+		let slowest = match self.positions.iter().flatten().min() {
+			Some(&slowest) => slowest,
+			None => self.start + self.buffer.len(),
+		};
+		
+		// Without other `Tee`s, new items aren't buffered at all
+		let passed = (slowest - self.start).min(self.buffer.len());
+		self.buffer.drain(..passed);
+		self.start = slowest;
+	}
+}
+
+
+/// One of a group of iterators that all return every item of the inner
+/// iterator. Created by `Splittable::tee` and `Splittable::broadcast`.
+///
+/// Items are buffered until every `Tee` that still exists has passed them,
+/// so the buffer only grows with the distance between the fastest and the
+/// slowest `Tee`. The last `Tee` to pass an item takes it without cloning.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct Tee<I> where
+	I: Iterator,
+	I::Item: Clone
+{
+	/// Shared state with the other `Tee`s.
+	shared: Rc<RefCell<SharedTeeState<I>>>,
+	/// Index of this `Tee` in the group.
+	id: usize,
+}
+
+/// Creates `count` `Tee`s for the given iterator.
+pub(crate) fn broadcast<I>(iter: I, count: usize) -> Vec<Tee<I>> where
+	I: Iterator,
+	I::Item: Clone
+{
+	let shared = Rc::new(
+		RefCell::new(
+			SharedTeeState::new(iter, count)
+		)
+	);
+	
+	(0..count)
+		.map(|id| Tee {
+			shared: shared.clone(),
+			id,
+		})
+		.collect()
+}
+
+impl<I> Iterator for Tee<I> where
+	I: Iterator,
+	I::Item: Clone
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.shared.borrow_mut().next(self.id)
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.shared.borrow().size_hint(self.id)
+	}
+}
+
+impl<I> FusedIterator for Tee<I> where
+	I: Iterator,
+	I::Item: Clone
+{}
+
+impl<I> Drop for Tee<I> where
+	I: Iterator,
+	I::Item: Clone
+{
+	fn drop(&mut self) {
+		if let Ok(mut shared) = self.shared.try_borrow_mut() {
+			shared.drop_tee(self.id);
+		}
+	}
+}
+
+impl<I> Debug for Tee<I> where
+	I: Iterator + Debug,
+	I::Item: Clone
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("Tee")
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::rc::Rc;
+	use std::cell::Cell;
+	use Splittable;
+	
+	#[test]
+	fn tee() {
+		let (mut a, b) = (0..5).tee();
+		assert_eq!(a.next(), Some(0));
+		assert_eq!(a.next(), Some(1));
+		assert_eq!(b.size_hint(), (5, Some(5)));
+		assert_eq!(a.size_hint(), (3, Some(3)));
+		assert_eq!(b.collect::<Vec<_>>(), [0,1,2,3,4]);
+		assert_eq!(a.collect::<Vec<_>>(), [2,3,4]);
+	}
+	
+	#[test]
+	fn buffer_is_bounded_by_distance() {
+		let item = Rc::new(());
+		let items = vec![item.clone(); 10];
+		let mut tees = items.into_iter().broadcast(3);
+		
+		tees[0].by_ref().take(6).for_each(drop);
+		tees[1].by_ref().take(2).for_each(drop);
+		assert_eq!(Rc::strong_count(&item), 1 + 4 + 6);
+		
+		tees[2].by_ref().take(4).for_each(drop);
+		assert_eq!(Rc::strong_count(&item), 1 + 4 + 4);
+		
+		drop(tees.remove(1));
+		assert_eq!(Rc::strong_count(&item), 1 + 4 + 2);
+		
+		drop(tees.remove(1));
+		assert_eq!(Rc::strong_count(&item), 1 + 4);
+		assert_eq!(tees[0].by_ref().count(), 4);
+		assert_eq!(Rc::strong_count(&item), 1);
+	}
+	
+	#[test]
+	fn clones_once_per_extra_consumer() {
+		/// Counts how often it is cloned.
+		struct Counted(Rc<Cell<usize>>);
+		
+		impl Clone for Counted {
+			fn clone(&self) -> Counted {
+				self.0.set(self.0.get() + 1);
+				Counted(self.0.clone())
+			}
+		}
+		
+		let clones = Rc::new(Cell::new(0));
+		let items = (0..10).map(|_| Counted(clones.clone())).collect::<Vec<_>>();
+		let mut tees = items.into_iter().broadcast(3);
+		
+		for tee in tees.iter_mut() {
+			assert_eq!(tee.count(), 10);
+		}
+		assert_eq!(clones.get(), 20);
+	}
+}
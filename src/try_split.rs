@@ -0,0 +1,199 @@
+use std::iter::Map;
+use std::iter::FusedIterator;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+
+use {Split, Classifier, PredicateError, SplitError};
+use route::Routed;
+
+
+/// Item of the iterators created by `Splittable::try_split`.
+pub type TryItem<T, E> = Result<T, PredicateError<T, E>>;
+
+/// One of a pair of iterators created by `Splittable::try_split`.
+pub type TrySplit<I, F, E> = Split<
+	Map<I, fn(<I as Iterator>::Item) -> TryItem<<I as Iterator>::Item, E>>,
+	TryPredicate<F>
+>;
+
+
+/// Classifier for a predicate that can fail. An item that the predicate
+/// fails on goes to the side that took it from the source, together with
+/// the error. Created by `Splittable::try_split`.
+#[derive(Clone, Debug)]
+pub struct TryPredicate<F>(pub(crate) F);
+
+impl<T, E, F> Classifier<TryItem<T, E>> for TryPredicate<F> where
+	F: FnMut(&T) -> Result<bool, E>
+{
+	fn classify(&mut self, _: Option<usize>, item: TryItem<T, E>)
+		-> Routed<TryItem<T, E>>
+	{
+		let item = match item {
+			Ok(item) => item,
+			Err(error) => return Routed::Caller(Err(error)),
+		};
+		
+		match (self.0)(&item) {
+			Ok(true) => Routed::Right(Ok(item)),
+			Ok(false) => Routed::Left(Ok(item)),
+			Err(error) => Routed::Caller(Err(PredicateError {
+				item,
+				error,
+			})),
+		}
+	}
+}
+
+
+/// Classifier for an iterator of `Result`s that routes the `Ok` values with
+/// a predicate and stops at the first `Err`. Created by
+/// `Splittable::split_ok_by`.
+#[derive(Clone, Debug)]
+pub struct OkBy<F>(pub(crate) F);
+
+impl<T, E, F> Classifier<Result<T, E>> for OkBy<F> where
+	F: FnMut(&T) -> bool
+{
+	fn classify(&mut self, _: Option<usize>, item: Result<T, E>)
+		-> Routed<Result<T, E>>
+	{
+		match item {
+			Ok(item) if (self.0)(&item) => Routed::Right(Ok(item)),
+			Ok(item) => Routed::Left(Ok(item)),
+			Err(error) => Routed::Failure(Err(error)),
+		}
+	}
+}
+
+
+/// One of a pair of iterators like `Split` over the `Result`s of an
+/// iterator, that both end at the first `Err`. Created by
+/// `Splittable::split_ok_by`.
+///
+/// Items can only be taken from the front, because items at the back might
+/// come after the first `Err`.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitOkBy<I, F> where
+	I: Iterator,
+	OkBy<F>: Classifier<I::Item>
+{
+	/// Split that returns the items.
+	split: Split<I, OkBy<F>>,
+}
+
+impl<I, F> SplitOkBy<I, F> where
+	I: Iterator,
+	OkBy<F>: Classifier<I::Item>
+{
+	/// Wraps a split.
+	pub(crate) fn new(split: Split<I, OkBy<F>>) -> SplitOkBy<I, F> {
+		SplitOkBy {
+			split,
+		}
+	}
+	
+	/// Returns the next item like `next`, but reports the end after an `Err`
+	/// returned by the opposite iterator as `SplitError::SourceFailed`, like
+	/// `Split::try_next`.
+	pub fn try_next(&mut self) -> Result<Option<I::Item>, SplitError> {
+		self.split.try_next()
+	}
+}
+
+impl<I, F> Iterator for SplitOkBy<I, F> where
+	I: Iterator,
+	OkBy<F>: Classifier<I::Item>
+{
+	type Item = I::Item;
+	
+	fn next(&mut self) -> Option<I::Item> {
+		self.split.next()
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.split.size_hint()
+	}
+}
+
+impl<I, F> FusedIterator for SplitOkBy<I, F> where
+	I: Iterator,
+	OkBy<F>: Classifier<I::Item>
+{}
+
+impl<I, F> Debug for SplitOkBy<I, F> where
+	I: Iterator + Debug,
+	OkBy<F>: Classifier<I::Item>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("SplitOkBy")
+			.field("split", &self.split)
+			.finish()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use {Splittable, PredicateError, SplitError};
+	
+	fn is_large(s: &&str) -> Result<bool, ::std::num::ParseIntError> {
+		s.parse::<i32>().map(|v| v >= 10)
+	}
+	
+	#[test]
+	fn error_goes_to_pulling_side() {
+		let (mut small, mut large) = vec!["1", "20", "x", "3", "y"].into_iter()
+			.try_split(is_large);
+		
+		assert_eq!(large.next(), Some(Ok("20")));
+		let error = large.next().unwrap().unwrap_err();
+		assert_eq!(error.item, "x");
+		assert_eq!(
+			error.to_string(),
+			"the predicate failed: invalid digit found in string"
+		);
+		assert_eq!(small.next(), Some(Ok("1")));
+		assert_eq!(small.next(), Some(Ok("3")));
+		assert_eq!(small.next().map(|r| r.map_err(|e| e.item)), Some(Err("y")));
+		assert_eq!(small.next(), None);
+		assert_eq!(large.next(), None);
+	}
+	
+	#[test]
+	fn errors_are_not_cached() {
+		let (mut small, large) = vec!["x", "y"].into_iter().try_split(is_large);
+		
+		assert!(matches!(small.next(), Some(Err(PredicateError { item: "x", .. }))));
+		assert_eq!(large.size_hint(), (0, Some(1)));
+	}
+	
+	#[test]
+	fn source_error_ends_both_sides() {
+		let (mut even, mut odd) = vec![Ok(1), Ok(2), Ok(4), Err("io"), Ok(3)]
+			.into_iter()
+			.split_ok_by(|v| v % 2 == 1);
+		
+		assert_eq!(odd.next(), Some(Ok(1)));
+		assert_eq!(odd.next(), Some(Err("io")));
+		assert_eq!(odd.try_next(), Ok(None));
+		assert_eq!(odd.size_hint(), (0, Some(0)));
+		
+		// Items taken from the source before the error are still returned
+		assert_eq!(even.try_next(), Ok(Some(Ok(2))));
+		assert_eq!(even.try_next(), Ok(Some(Ok(4))));
+		assert_eq!(even.try_next(), Err(SplitError::SourceFailed));
+		assert_eq!(even.next(), None);
+	}
+	
+	#[test]
+	fn items_before_error_are_kept() {
+		let (even, mut odd) = vec![Ok(0), Ok(1), Err("e"), Ok(3)]
+			.into_iter()
+			.split_ok_by(|v| v % 2 == 1);
+		
+		assert_eq!(odd.by_ref().collect::<Vec<_>>(), [Ok(1), Err("e")]);
+		assert_eq!(even.collect::<Vec<_>>(), [Ok(0)]);
+	}
+}
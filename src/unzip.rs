@@ -0,0 +1,299 @@
+use std::rc::Rc;
+use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Error as FmtError;
+use std::iter::FusedIterator;
+
+
+/// A tuple that can be lazily unzipped into columns. Implemented for tuples
+/// with 2 to 4 elements.
+pub trait Tuple: Sized {
+	/// One cache per column. A cache is `None` once its column is dropped.
+	type Queues;
+	
+	/// Creates empty caches for all columns.
+	fn queues() -> Self::Queues;
+	
+	/// Caches every element of the tuple for its column, unless the column
+	/// has been dropped.
+	fn scatter(self, queues: &mut Self::Queues);
+}
+
+/// The element at index `K` of a `Tuple`.
+pub trait Field<const K: usize>: Tuple {
+	/// Type of the element.
+	type Output;
+	
+	/// Returns the cache of the column for the element.
+	fn queue(queues: &mut Self::Queues) -> &mut Option<VecDeque<Self::Output>>;
+}
+
+/// Unzips an iterator of tuples into one `Column` per element. Implemented
+/// for tuples with 2 to 4 elements.
+pub trait UnzipLazy<I>: Tuple where
+	I: Iterator<Item = Self>
+{
+	/// Tuple of `Column`s.
+	type Columns;
+	
+	/// Unzips `iter` into one `Column` per element.
+	fn unzip_lazy(iter: I) -> Self::Columns;
+}
+
+
+macro_rules! tuple {
+	($($name:ident $index:tt),+) => {
+		impl<$($name),+> Tuple for ($($name,)+) {
+			type Queues = ($(Option<VecDeque<$name>>,)+);
+			
+			fn queues() -> Self::Queues {
+				($(Some(VecDeque::<$name>::new()),)+)
+			}
+			
+			fn scatter(self, queues: &mut Self::Queues) {
+				$(
+					if let Some(ref mut queue) = queues.$index {
+						queue.push_back(self.$index);
+					}
+				)+
+			}
+		}
+		
+		impl<I, $($name),+> UnzipLazy<I> for ($($name,)+) where
+			I: Iterator<Item = ($($name,)+)>
+		{
+			type Columns = ($(Column<I, $index>,)+);
+			
+			fn unzip_lazy(iter: I) -> Self::Columns {
+				let shared = Rc::new(
+					RefCell::new(
+						SharedUnzipState {
+							iter,
+							queues: Self::queues(),
+							is_exhausted: false,
+						}
+					)
+				);
+				
+				($(
+					Column::<I, $index> {
+						shared: shared.clone(),
+					},
+				)+)
+			}
+		}
+	};
+}
+
+macro_rules! field {
+	(($($name:ident),+) $index:tt $output:ident) => {
+		impl<$($name),+> Field<$index> for ($($name,)+) {
+			type Output = $output;
+			
+			fn queue(queues: &mut Self::Queues) -> &mut Option<VecDeque<$output>> {
+				&mut queues.$index
+			}
+		}
+	};
+}
+
+tuple!(A 0, B 1);
+field!((A, B) 0 A);
+field!((A, B) 1 B);
+
+tuple!(A 0, B 1, C 2);
+field!((A, B, C) 0 A);
+field!((A, B, C) 1 B);
+field!((A, B, C) 2 C);
+
+tuple!(A 0, B 1, C 2, D 3);
+field!((A, B, C, D) 0 A);
+field!((A, B, C, D) 1 B);
+field!((A, B, C, D) 2 C);
+field!((A, B, C, D) 3 D);
+
+
+/// Shared inner state for the `Column`s of an iterator.
+struct SharedUnzipState<I> where
+	I: Iterator,
+	I::Item: Tuple
+{
+	/// Inner iterator.
+	iter: I,
+	/// Elements that have been taken from the inner iterator, but not yet by
+	/// their `Column`.
+	queues: <I::Item as Tuple>::Queues,
+	/// Has the inner iterator returned `None`?
+	is_exhausted: bool,
+}
+
+impl<I> SharedUnzipState<I> where
+	I: Iterator,
+	I::Item: Tuple
+{
+	/// Returns the next element for the column `K`.
+	fn next<const K: usize>(&mut self) -> Option<<I::Item as Field<K>>::Output> where
+		I::Item: Field<K>
+	{
+		loop {
+			// Use cache
+			let queue = <I::Item as Field<K>>::queue(&mut self.queues);
+			if let Some(next) = queue.as_mut().and_then(VecDeque::pop_front) {
+				return Some(next);
+			}
+			
+			if self.is_exhausted {
+				return None;
+			}
+			
+			// From inner iterator
+			match self.iter.next() {
+				Some(next) => next.scatter(&mut self.queues),
+				None => self.is_exhausted = true,
+			}
+		}
+	}
+	
+	/// Returns the bounds on the remaining number of elements for column `K`.
+	fn size_hint<const K: usize>(&mut self) -> (usize, Option<usize>) where
+		I::Item: Field<K>
+	{
+		let queue = <I::Item as Field<K>>::queue(&mut self.queues);
+		let cached = queue.as_ref().map_or(0, VecDeque::len);
+		if self.is_exhausted {
+			return (cached, Some(cached));
+		}
+		
+		let (lower, upper) = self.iter.size_hint();
+		(
+			lower.saturating_add(cached),
+			upper.and_then(|upper| upper.checked_add(cached)),
+		)
+	}
+}
+
+
+/// Iterator over the elements at index `K` of an iterator of tuples.
+/// Created by `unzip_lazy`.
+///
+/// Each element is cached until its `Column` takes it, so the `Column`s
+/// that are behind hold the elements that the ones in front have already
+/// passed. Dropped `Column`s don't cache anything.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct Column<I, const K: usize> where
+	I: Iterator,
+	I::Item: Field<K>
+{
+	/// Shared state with the other columns.
+	shared: Rc<RefCell<SharedUnzipState<I>>>,
+}
+
+impl<I, const K: usize> Iterator for Column<I, K> where
+	I: Iterator,
+	I::Item: Field<K>
+{
+	type Item = <I::Item as Field<K>>::Output;
+	
+	fn next(&mut self) -> Option<Self::Item> {
+		self.shared.borrow_mut().next::<K>()
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.shared.borrow_mut().size_hint::<K>()
+	}
+}
+
+impl<I, const K: usize> FusedIterator for Column<I, K> where
+	I: Iterator,
+	I::Item: Field<K>
+{}
+
+impl<I, const K: usize> Drop for Column<I, K> where
+	I: Iterator,
+	I::Item: Field<K>
+{
+	fn drop(&mut self) {
+		if let Ok(mut shared) = self.shared.try_borrow_mut() {
+			*<I::Item as Field<K>>::queue(&mut shared.queues) = None;
+		}
+	}
+}
+
+impl<I, const K: usize> Debug for Column<I, K> where
+	I: Iterator + Debug,
+	I::Item: Field<K>
+{
+	fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+		fmt.debug_struct("Column")
+			.field("index", &K)
+			.field("iter", &self.shared.borrow().iter)
+			.finish()
+	}
+}
+
+
+/// Lazily unzips an iterator of tuples into one iterator per element.
+///
+/// Unlike `Iterator::unzip`, nothing is collected up front: each column
+/// only caches the elements that it hasn't taken yet, but that another
+/// column has already passed.
+///
+/// # Example
+///
+/// ```
+/// let pairs = vec![(1, 'a'), (2, 'b'), (3, 'c')];
+/// let (numbers, letters) = split_iter::unzip_lazy(pairs.into_iter());
+///
+/// assert_eq!(letters.collect::<String>(), "abc");
+/// assert_eq!(numbers.collect::<Vec<_>>(), [1,2,3]);
+/// ```
+pub fn unzip_lazy<I>(iter: I) -> <I::Item as UnzipLazy<I>>::Columns where
+	I: Iterator,
+	I::Item: UnzipLazy<I>
+{
+	<I::Item as UnzipLazy<I>>::unzip_lazy(iter)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::rc::Rc;
+	use super::unzip_lazy;
+	
+	#[test]
+	fn pairs() {
+		let (mut a, mut b) = unzip_lazy((0..5).map(|v| (v, v * 10)));
+		assert_eq!(b.size_hint(), (5, Some(5)));
+		assert_eq!(b.next(), Some(0));
+		assert_eq!(b.next(), Some(10));
+		assert_eq!(a.size_hint(), (5, Some(5)));
+		assert_eq!(a.next(), Some(0));
+		assert_eq!(b.size_hint(), (3, Some(3)));
+		assert_eq!(b.collect::<Vec<_>>(), [20,30,40]);
+		assert_eq!(a.collect::<Vec<_>>(), [1,2,3,4]);
+	}
+	
+	#[test]
+	fn quadruples() {
+		let rows = vec![(1, "a", 'x', 1.5), (2, "b", 'y', 2.5)];
+		let (a, b, c, d) = unzip_lazy(rows.into_iter());
+		assert_eq!(d.collect::<Vec<_>>(), [1.5, 2.5]);
+		assert_eq!(c.collect::<String>(), "xy");
+		assert_eq!(b.collect::<Vec<_>>(), ["a", "b"]);
+		assert_eq!(a.collect::<Vec<_>>(), [1,2]);
+	}
+	
+	#[test]
+	fn dropped_column_is_not_cached() {
+		let item = Rc::new(());
+		let rows = (0..3).map(|v| (v, item.clone(), v)).collect::<Vec<_>>();
+		let (a, b, c) = unzip_lazy(rows.into_iter());
+		
+		drop(b);
+		assert_eq!(c.collect::<Vec<_>>(), [0,1,2]);
+		assert_eq!(Rc::strong_count(&item), 1);
+		assert_eq!(a.collect::<Vec<_>>(), [0,1,2]);
+	}
+}